@@ -4,7 +4,7 @@ use crate::data::package;
 
 pub fn execute_collapse_operation() {
     let package = package::PackageConfig::read();
-    let resolved = package.resolve();
+    let resolved = package.resolve(Default::default());
     for shared_package in resolved {
         println!(
             "{}: ({}) --> {} ({} restored dependencies)",
@@ -6,6 +6,7 @@ mod symlink;
 mod timeout;
 mod token;
 mod publish;
+mod registry;
 
 use owo_colors::OwoColorize;
 
@@ -39,6 +40,8 @@ pub enum ConfigOperation {
     NDKPath(ndkpath::NDKPath),
     /// Get or set the publish key used for publish
     Publish(publish::Key),
+    /// Get or set the url of the package registry
+    Registry(registry::Registry),
 }
 
 pub fn execute_config_operation(operation: Config) {
@@ -68,6 +71,9 @@ pub fn execute_config_operation(operation: Config) {
             changed_any = ndkpath::execute_ndk_config_operation(&mut config, p)
         },
         ConfigOperation::Publish(k) => publish::execute_key_config_operation(k),
+        ConfigOperation::Registry(r) => {
+            changed_any = registry::execute_registry_config_operation(&mut config, r)
+        }
     }
 
     if !changed_any {
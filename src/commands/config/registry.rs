@@ -0,0 +1,31 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::data::config::Config as AppConfig;
+
+#[derive(Args, Debug, Clone)]
+pub struct Registry {
+    /// url of the registry api
+    pub url: Option<String>,
+    /// Go back to using qpackages.com
+    #[clap(long)]
+    pub reset: bool,
+}
+
+pub fn execute_registry_config_operation(config: &mut AppConfig, operation: Registry) -> bool {
+    if operation.reset {
+        println!("Reset the registry, qpackages.com will be used");
+        config.registry = None;
+        true
+    } else if let Some(url) = operation.url {
+        println!("Set registry to {}!", url.bright_yellow());
+        config.registry = Some(url);
+        true
+    } else if let Some(url) = &config.registry {
+        println!("Current configured registry is set to: {}", url.bright_yellow());
+        false
+    } else {
+        println!("Registry is not configured, qpackages.com is used!");
+        false
+    }
+}
@@ -30,6 +30,10 @@ pub struct DependencyOperationAddArgs {
     /// Additional data for the dependency (as a valid json object)
     #[clap(long)]
     pub additional_data: Option<String>,
+
+    /// Add it as a dev dependency, which is not published and not added to the mod.json
+    #[clap(long)]
+    pub dev: bool,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -74,13 +78,14 @@ fn add_dependency(dependency_args: DependencyOperationAddArgs) {
         Option::None => dependency::AdditionalDependencyData::default(),
     };
 
-    put_dependency(&dependency_args.id, version, &additional_data);
+    put_dependency(&dependency_args.id, version, &additional_data, dependency_args.dev);
 }
 
 fn put_dependency(
     id: &str,
     version: VersionReq,
     additional_data: &dependency::AdditionalDependencyData,
+    dev: bool,
 ) {
     println!(
         "Adding dependency with id {} and version {}",
@@ -94,7 +99,11 @@ fn put_dependency(
         version_range: version,
         additional_data: additional_data.clone(),
     };
-    package.add_dependency(dep);
+    if dev {
+        package.add_dev_dependency(dep);
+    } else {
+        package.add_dependency(dep);
+    }
     package.write();
 }
 
@@ -0,0 +1,160 @@
+use std::fmt::Display;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use semver::Version;
+
+use crate::data::{
+    file_repository::FileRepository,
+    package::CompileOptions,
+    repo::{multi_provider::MultiDependencyProvider, DependencyRepository},
+};
+
+#[derive(Args, Debug, Clone)]
+pub struct InfoOperation {
+    /// The package to show, as `id` or `id@version`, the newest version is shown if none is given
+    pub package: String,
+
+    /// Only look in the local file repository that `qpm install` publishes to
+    #[clap(long)]
+    pub local: bool,
+}
+
+pub fn execute_info_operation(operation: InfoOperation) {
+    let repo: Box<dyn DependencyRepository> = if operation.local {
+        Box::new(FileRepository::read())
+    } else {
+        Box::new(MultiDependencyProvider::useful_default_new())
+    };
+
+    let (id, version) = match operation.package.split_once('@') {
+        Some((id, version)) => (
+            id,
+            Some(Version::parse(version).unwrap_or_else(|e| {
+                panic!("Invalid version {}: {}", version.bright_yellow(), e)
+            })),
+        ),
+        None => (operation.package.as_str(), None),
+    };
+
+    let version = version.unwrap_or_else(|| {
+        repo.get_versions(id)
+            .into_iter()
+            .flatten()
+            .filter(|v| !v.yanked.unwrap_or(false))
+            .map(|v| v.version)
+            .max()
+            .unwrap_or_else(|| panic!("Could not find package {}", id.bright_red()))
+    });
+
+    let shared_package = repo
+        .get_shared_package(id, &version)
+        .unwrap_or_else(|| panic!("Could not find package {} {}", id.bright_red(), version));
+    let config = &shared_package.config;
+    let info = &config.info;
+    let data = &info.additional_data;
+
+    println!(
+        "{} {} ({})",
+        info.id.bright_red(),
+        info.version.bright_green(),
+        info.name
+    );
+    print_field("description", &info.description);
+    print_field("url", &info.url);
+    print_field("branch", &data.branch_name);
+    print_field("soLink", &data.so_link);
+    print_field("debugSoLink", &data.debug_so_link);
+    print_field("modLink", &data.mod_link);
+    print_field("staticLinking", &data.static_linking);
+    print_field("headersOnly", &data.headers_only);
+    print_field("overrideSoName", &data.override_so_name);
+    print_field("subFolder", &data.sub_folder);
+    if let Some(game) = &info.game {
+        println!("  {}: {} {}", "game".bright_yellow(), game.id, game.version_range);
+    }
+
+    if let Some(compile_options) = &data.compile_options {
+        println!("\nCompile options:");
+        print_compile_options(compile_options);
+    }
+
+    if !config.targets.is_empty() {
+        println!("\nTargets:");
+        for target in config.targets.iter() {
+            println!(
+                "  {}: platform {}, {}",
+                target.abi.bright_yellow(),
+                target.platform,
+                target.stl
+            );
+            print_field("soLink", &data.get_so_link(&target.abi));
+            print_field("debugSoLink", &data.get_debug_so_link(&target.abi));
+        }
+    }
+
+    if !config.features.is_empty() {
+        println!("\nFeatures:");
+        for (name, feature) in config.features.iter() {
+            println!("  {}: {}", name.bright_yellow(), feature.dependencies.join(", "));
+        }
+    }
+
+    println!("\nDependencies:");
+    if config.dependencies.is_empty() {
+        println!("  none");
+    }
+    for dependency in config.dependencies.iter() {
+        let mut flags = Vec::new();
+        if dependency.additional_data.is_private.unwrap_or(false) {
+            flags.push("private");
+        }
+        if dependency.additional_data.optional.unwrap_or(false) {
+            flags.push("optional");
+        }
+        println!(
+            "  {}: {}{}",
+            dependency.id,
+            dependency.version_range.bright_blue(),
+            if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", flags.join(", "))
+            }
+        );
+    }
+
+    println!("\nRestored dependencies:");
+    if shared_package.restored_dependencies.is_empty() {
+        println!("  none");
+    }
+    for restored in shared_package.restored_dependencies.iter() {
+        println!(
+            "  {}: {} -> {}",
+            restored.dependency.id,
+            restored.dependency.version_range.bright_blue(),
+            restored.version.bright_green()
+        );
+    }
+}
+
+fn print_field(name: &str, value: &Option<impl Display>) {
+    if let Some(value) = value {
+        println!("  {}: {}", name.bright_yellow(), value);
+    }
+}
+
+fn print_compile_options(compile_options: &CompileOptions) {
+    for (name, values) in [
+        ("includePaths", &compile_options.include_paths),
+        ("systemIncludes", &compile_options.system_includes),
+        ("cppFeatures", &compile_options.cpp_features),
+        ("cppFlags", &compile_options.cpp_flags),
+        ("cFlags", &compile_options.c_flags),
+        ("defines", &compile_options.defines),
+    ] {
+        if let Some(values) = values {
+            println!("  {}: {}", name.bright_yellow(), values.join(" "));
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use semver::{Version, VersionReq};
+use walkdir::WalkDir;
+
+use crate::{
+    commands::{
+        package::create::{package_create_operation, PackageOperationCreateArgs},
+        qmod::{execute_qmod_create_operation, CreateQmodJsonOperationArgs},
+    },
+    data::{config::Config, package::PackageConfig},
+    utils::template,
+};
+
+/// Files every builtin template has, cmake variables are escaped as $${NAME}
+const CMAKE_LISTS: &str = include_str!("templates/common/CMakeLists.txt");
+const BUILD_SCRIPT: &str = include_str!("templates/common/build.ps1");
+const GITIGNORE: &str = include_str!("templates/common/gitignore");
+
+/// The templates qpm ships with, written to the templates folder when they are not there yet
+const BUILTIN_TEMPLATES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "mod",
+        &[
+            ("CMakeLists.txt", CMAKE_LISTS),
+            ("src/main.cpp", include_str!("templates/mod/src/main.cpp")),
+            (".gitignore", GITIGNORE),
+            ("build.ps1", BUILD_SCRIPT),
+        ],
+    ),
+    (
+        "library",
+        &[
+            ("CMakeLists.txt", CMAKE_LISTS),
+            ("src/main.cpp", include_str!("templates/library/src/main.cpp")),
+            ("shared/${id}.hpp", include_str!("templates/library/shared/header.hpp")),
+            (".gitignore", GITIGNORE),
+            ("build.ps1", BUILD_SCRIPT),
+        ],
+    ),
+];
+
+#[derive(Args, Debug, Clone)]
+pub struct InitOperation {
+    /// Use the default for everything that is not given instead of asking
+    #[clap(long, short)]
+    pub yes: bool,
+
+    /// The template to render, a folder in the templates folder of the qpm config dir
+    #[clap(long, default_value = "mod")]
+    pub template: String,
+
+    /// The name of the package, defaults to the name of the current folder
+    #[clap(long)]
+    pub name: Option<String>,
+
+    /// The id of the package, defaults to the name in lowercase
+    #[clap(long)]
+    pub id: Option<String>,
+
+    /// The version of the package
+    #[clap(long)]
+    pub version: Option<Version>,
+
+    /// Author of the mod
+    #[clap(long)]
+    pub author: Option<String>,
+
+    /// id of the game the mod is for, ex. 'com.beatgames.beatsaber'
+    #[clap(long)]
+    pub game_id: Option<String>,
+
+    /// Version of the game the mod is for, ex. '1.28.0'
+    #[clap(long)]
+    pub game_version: Option<String>,
+}
+
+pub fn execute_init_operation(operation: InitOperation) {
+    if PackageConfig::check() {
+        panic!(
+            "A package already exists in this folder, use {} to change it",
+            "qpm package edit".bright_yellow()
+        );
+    }
+
+    let template_dir = template_dir(&operation.template);
+    let ask = |question: &str, given: Option<String>, default: String| match given {
+        Some(value) => value,
+        None if operation.yes => default,
+        None => prompt(question, default),
+    };
+
+    let folder_name = std::env::current_dir()
+        .expect("Unable to get current directory")
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mod".to_string());
+    let name = ask("name", operation.name, folder_name);
+    let id = ask("id", operation.id, name.to_lowercase().replace(' ', "-"));
+    let version = ask(
+        "version",
+        operation.version.map(|v| v.to_string()),
+        "0.1.0".to_string(),
+    );
+    let version = Version::parse(&version)
+        .unwrap_or_else(|_| panic!("{} is not a valid version", version.bright_red()));
+    let author = ask("author", operation.author, "---".to_string());
+    let game_id = ask(
+        "game id",
+        operation.game_id,
+        "com.beatgames.beatsaber".to_string(),
+    );
+    let game_version = ask("game version (empty for any)", operation.game_version, String::new());
+    let game_version = (!game_version.is_empty()).then(|| {
+        Version::parse(&game_version)
+            .unwrap_or_else(|_| panic!("{} is not a valid game version", game_version.bright_red()))
+    });
+
+    package_create_operation(PackageOperationCreateArgs {
+        name: name.clone(),
+        version: version.clone(),
+        id: Some(id.clone()),
+        branch_name: None,
+        headers_only: None,
+        static_linking: None,
+        so_link: None,
+        debug_so_link: None,
+        override_so_name: None,
+        game_id: Some(game_id.clone()),
+        game_version: game_version.as_ref().map(|v| {
+            VersionReq::parse(&format!("={}", v)).expect("Failed to make game version range")
+        }),
+    });
+
+    execute_qmod_create_operation(CreateQmodJsonOperationArgs {
+        schema_version: None,
+        author: Some(author.clone()),
+        porter: None,
+        package_id: Some(game_id.clone()),
+        package_version: game_version.as_ref().map(|v| v.to_string()),
+        description: None,
+        cover_image: None,
+        is_library: Some(operation.template == "library"),
+    });
+
+    let variables = HashMap::from([
+        ("name", name.clone()),
+        ("id", id),
+        ("version", version.to_string()),
+        ("author", author),
+        ("mod_id", name.replace(' ', "")),
+        ("game_id", game_id),
+        (
+            "game_version",
+            game_version.map(|v| v.to_string()).unwrap_or_default(),
+        ),
+    ]);
+    render_template(&template_dir, &variables);
+
+    println!(
+        "Created {} from template {}, run {} to get started",
+        name.bright_green(),
+        operation.template.bright_yellow(),
+        "qpm restore".bright_yellow()
+    );
+}
+
+fn prompt(question: &str, default: String) -> String {
+    if default.is_empty() {
+        print!("{}: ", question);
+    } else {
+        print!("{} ({}): ", question, default.bright_yellow());
+    }
+    std::io::stdout().flush().expect("Failed to flush stdout");
+
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .expect("Failed to read answer");
+    match line.trim() {
+        "" => default,
+        answer => answer.to_string(),
+    }
+}
+
+/// The folder the templates are read from, users can change them or add their own
+pub fn templates_dir() -> PathBuf {
+    Config::global_config_dir().join("templates")
+}
+
+/// The folder of a template, writing the builtin template there first if it did not exist yet
+fn template_dir(template: &str) -> PathBuf {
+    let dir = templates_dir().join(template);
+    if dir.exists() {
+        return dir;
+    }
+
+    let (_, files) = BUILTIN_TEMPLATES
+        .iter()
+        .find(|(name, _)| *name == template)
+        .unwrap_or_else(|| {
+            let mut available: Vec<String> = BUILTIN_TEMPLATES
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect();
+            if let Ok(entries) = std::fs::read_dir(templates_dir()) {
+                available.extend(
+                    entries
+                        .flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().to_string()),
+                );
+            }
+            available.sort();
+            available.dedup();
+            panic!(
+                "Template {} does not exist, available templates: {}",
+                template.bright_red(),
+                available.join(", ").bright_yellow()
+            )
+        });
+
+    for (path, content) in files.iter() {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to make template folder");
+        std::fs::write(&path, content).expect("Failed to write template file");
+    }
+    dir
+}
+
+/// Writes every file of the template to the current folder, file names and contents are templates like mod.template.json
+fn render_template(template_dir: &Path, variables: &HashMap<&str, String>) {
+    for entry in WalkDir::new(template_dir) {
+        let entry = entry.expect("Failed to read template folder");
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry
+            .path()
+            .strip_prefix(template_dir)
+            .unwrap()
+            .to_string_lossy()
+            .replace('\\', "/");
+        let target = PathBuf::from(render(&relative, variables));
+        if target.exists() {
+            println!(
+                "Skipping {}, it already exists",
+                target.display().bright_yellow()
+            );
+            continue;
+        }
+
+        let content = std::fs::read_to_string(entry.path()).unwrap_or_else(|_| {
+            panic!(
+                "Failed to read template file {}",
+                entry.path().display().bright_red()
+            )
+        });
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).expect("Failed to create folder");
+        }
+        std::fs::write(&target, render(&content, variables)).expect("Failed to write file");
+    }
+}
+
+fn render(text: &str, variables: &HashMap<&str, String>) -> String {
+    template::render(text, |name| variables.get(name).cloned())
+}
@@ -0,0 +1,20 @@
+Param(
+    [Parameter(Mandatory=$false)]
+    [Switch] $clean,
+
+    [Parameter(Mandatory=$false)]
+    [String] $abi = ""
+)
+
+# packages with targets build every abi in its own folder, qpm looks for their binaries in build/<abi>
+# the abi that is built is the one restored with "qpm restore --target <abi>"
+$buildDir = if ($abi) { "build/$abi" } else { "build" }
+
+# clean the build folder if asked
+if ($clean.IsPresent -and (Test-Path -Path $buildDir)) {
+    Remove-Item $buildDir -Recurse
+}
+
+& cmake -G "Ninja" -DCMAKE_BUILD_TYPE="RelWithDebInfo" -B $buildDir
+& cmake --build $buildDir
+exit $LASTEXITCODE
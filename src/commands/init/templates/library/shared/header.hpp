@@ -0,0 +1,8 @@
+#pragma once
+
+// ${name} v${version} by ${author}
+
+namespace ${mod_id} {
+    /// The version of ${name} that is loaded
+    const char* version();
+}
@@ -0,0 +1,11 @@
+#include <android/log.h>
+
+#include "${id}.hpp"
+
+const char* ${mod_id}::version() {
+    return VERSION;
+}
+
+extern "C" void setup() {
+    __android_log_print(ANDROID_LOG_INFO, MOD_ID, "Setting up ${name} v%s", VERSION);
+}
@@ -0,0 +1,11 @@
+#include <android/log.h>
+
+// ${name} v${version} by ${author}
+
+extern "C" void setup() {
+    __android_log_print(ANDROID_LOG_INFO, MOD_ID, "Setting up ${name} v%s", VERSION);
+}
+
+extern "C" void load() {
+    __android_log_print(ANDROID_LOG_INFO, MOD_ID, "Loading ${name}");
+}
@@ -2,10 +2,14 @@ use std::{io::Write, path::PathBuf};
 
 use clap::Args;
 
-use crate::data::{
-    config::Config,
-    file_repository::FileRepository,
-    package::{PackageConfig, SharedPackageConfig},
+use crate::{
+    data::{
+        config::Config,
+        file_repository::FileRepository,
+        package::{PackageConfig, SharedPackageConfig},
+        workspace::WorkspaceConfig,
+    },
+    resolver::ResolutionStrategy,
 };
 
 #[derive(Args, Debug, Clone)]
@@ -15,14 +19,36 @@ pub struct InstallOperation {
 
     #[clap(long)]
     pub cmake_build: Option<bool>,
+
+    /// Which versions to pick, by default versions already in qpm.shared.json are kept
+    #[clap(long, value_enum)]
+    pub resolution: Option<ResolutionStrategy>,
     // pub additional_folders: Vec<String> // todo
 }
 
 pub fn execute_install_operation(install: InstallOperation) {
+    let strategy = install.resolution.unwrap_or_default();
+    if WorkspaceConfig::check() {
+        if install.binary_path.is_some() || install.debug_binary_path.is_some() {
+            panic!("Binary paths can not be given when installing a workspace, every member uses its own build folder");
+        }
+
+        println!("Publishing workspace to local file repository");
+        for (member, shared_package) in WorkspaceConfig::read().resolve(strategy) {
+            WorkspaceConfig::in_member(&member, || {
+                install_package(install.clone(), shared_package)
+            });
+        }
+        return;
+    }
+
     println!("Publishing package to local file repository");
     let package = PackageConfig::read();
-    let shared_package = SharedPackageConfig::from_package(&package);
+    let shared_package = SharedPackageConfig::from_package(&package, strategy);
+    install_package(install, shared_package);
+}
 
+pub fn install_package(install: InstallOperation, shared_package: SharedPackageConfig) {
     // create used dirs
     std::fs::create_dir_all("src").expect("Failed to create directory");
     std::fs::create_dir_all("include").expect("Failed to create directory");
@@ -41,14 +67,23 @@ pub fn execute_install_operation(install: InstallOperation) {
     let mut binary_path = install.binary_path;
     let mut debug_binary_path = install.debug_binary_path;
 
-    let header_only = package.info.additional_data.headers_only.unwrap_or(false);
+    let header_only = shared_package
+        .config
+        .info
+        .additional_data
+        .headers_only
+        .unwrap_or(false);
     #[cfg(debug_assertions)]
     println!("Header only: {}", header_only);
 
     if !header_only {
+        // the local file repository keeps one binary, the one of the first target
+        let config = &shared_package.config;
+        let build_dir = config.get_build_dir(&config.get_target(None));
         if binary_path.is_none() && install.cmake_build.unwrap_or(true) {
             binary_path = Some(
-                PathBuf::from(format!("./build/{}", shared_package.config.get_so_name()))
+                build_dir
+                    .join(shared_package.config.get_so_name())
                     .canonicalize()
                     .unwrap(),
             );
@@ -56,12 +91,11 @@ pub fn execute_install_operation(install: InstallOperation) {
 
         if debug_binary_path.is_none() && install.cmake_build.unwrap_or(true) {
             debug_binary_path = Some(
-                PathBuf::from(format!(
-                    "./build/debug/{}",
-                    shared_package.config.get_so_name()
-                ))
-                .canonicalize()
-                .unwrap(),
+                build_dir
+                    .join("debug")
+                    .join(shared_package.config.get_so_name())
+                    .canonicalize()
+                    .unwrap(),
             );
         }
     }
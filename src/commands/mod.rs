@@ -3,9 +3,16 @@ pub mod clear;
 pub mod collapse;
 pub mod config;
 pub mod dependency;
+pub mod info;
+pub mod init;
 pub mod list;
+pub mod outdated;
 pub mod package;
 pub mod publish;
 pub mod qmod;
+pub mod rdeps;
+pub mod registry;
 pub mod restore;
+pub mod search;
+pub mod yank;
 pub mod install;
\ No newline at end of file
@@ -0,0 +1,123 @@
+use owo_colors::OwoColorize;
+
+use crate::data::{
+    package::{PackageConfig, SharedPackageConfig},
+    repo::{multi_provider::MultiDependencyProvider, DependencyRepository},
+    workspace::WorkspaceConfig,
+};
+
+pub fn execute_outdated_operation() {
+    if WorkspaceConfig::check() {
+        let workspace = WorkspaceConfig::read();
+        let members = workspace.read_members();
+        for (member, _) in members.iter() {
+            WorkspaceConfig::in_member(member, || {
+                print_outdated(|id| members.iter().any(|(_, p)| p.info.id == id))
+            });
+        }
+        return;
+    }
+
+    print_outdated(|_| false);
+}
+
+/// Prints the locked, newest matching and newest version for every direct dependency
+fn print_outdated(is_member: impl Fn(&str) -> bool) {
+    let package = PackageConfig::read();
+    let locked = if std::path::Path::new("qpm.shared.json").exists() {
+        Some(SharedPackageConfig::read())
+    } else {
+        None
+    };
+    let repo = MultiDependencyProvider::useful_default_new();
+
+    println!(
+        "Dependencies of {} {}:",
+        package.info.id.bright_red(),
+        package.info.version.bright_green()
+    );
+
+    for dependency in package.all_dependencies() {
+        if is_member(&dependency.id) || dependency.additional_data.local_path.is_some() {
+            println!(" - {}: local", dependency.id);
+            continue;
+        }
+
+        let locked_version = locked.as_ref().and_then(|shared_package| {
+            shared_package
+                .restored_dependencies
+                .iter()
+                .find(|restored| restored.dependency.id == dependency.id)
+                .map(|restored| restored.version.clone())
+        });
+
+        let versions = match repo.get_versions(&dependency.id) {
+            Some(versions) => versions,
+            None => {
+                println!(
+                    " - {}: not found on any repository",
+                    dependency.id.bright_red()
+                );
+                continue;
+            }
+        };
+
+        if let Some(yanked) = versions
+            .iter()
+            .find(|v| Some(&v.version) == locked_version.as_ref() && v.yanked.unwrap_or(false))
+        {
+            println!(
+                " - {}: locked version {} has been yanked{}",
+                dependency.id.bright_red(),
+                yanked.version.bright_yellow(),
+                yanked
+                    .yanked_reason
+                    .as_ref()
+                    .map(|reason| format!(": {reason}"))
+                    .unwrap_or_default()
+            );
+        }
+
+        let versions: Vec<_> = versions
+            .into_iter()
+            .filter(|v| !v.yanked.unwrap_or(false))
+            .collect();
+        let latest = match versions.iter().map(|v| &v.version).max() {
+            Some(latest) => latest,
+            None => {
+                println!(" - {}: every version has been yanked", dependency.id.bright_red());
+                continue;
+            }
+        };
+        let latest_matching = versions
+            .iter()
+            .map(|v| &v.version)
+            .filter(|v| dependency.version_range.matches(v))
+            .max();
+
+        let locked_str = locked_version
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let matching_str = latest_matching
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        if locked_version.as_ref() == Some(latest) {
+            println!(
+                " - {}: {} (up to date)",
+                dependency.id,
+                locked_str.bright_green()
+            );
+        } else {
+            println!(
+                " - {} ({}): {} -> {} (latest {})",
+                dependency.id.bright_red(),
+                dependency.version_range.bright_blue(),
+                locked_str.bright_yellow(),
+                matching_str.bright_green(),
+                latest.bright_green()
+            );
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use clap::{ArgGroup, Args};
+use owo_colors::OwoColorize;
+use semver::{BuildMetadata, Prerelease, Version};
+use walkdir::WalkDir;
+
+use crate::data::{
+    dependency::SharedDependency,
+    package::PackageConfig,
+    repo::{multi_provider::MultiDependencyProvider, DependencyRepository},
+};
+
+use super::edit::{package_set_version, write_edited_package};
+
+#[derive(Args, Debug, Clone)]
+#[clap(group(ArgGroup::new("bump").required(true).args(&["major", "minor", "patch", "auto"])))]
+pub struct BumpArgs {
+    /// Bump the major version, for breaking changes
+    #[clap(long)]
+    pub major: bool,
+    /// Bump the minor version, for added features
+    #[clap(long)]
+    pub minor: bool,
+    /// Bump the patch version, for fixes
+    #[clap(long)]
+    pub patch: bool,
+    /// Pick the bump by comparing the shared folder with the last published version
+    #[clap(long)]
+    pub auto: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+pub fn package_bump_operation(bump_parameters: BumpArgs) {
+    let mut package = PackageConfig::read();
+
+    let bump = if bump_parameters.major {
+        Bump::Major
+    } else if bump_parameters.minor {
+        Bump::Minor
+    } else if bump_parameters.patch {
+        Bump::Patch
+    } else {
+        suggest_bump(&package)
+    };
+
+    let version = bumped(&package.info.version, bump);
+    package_set_version(&mut package, version);
+    write_edited_package(package);
+}
+
+/// Increments the bumped part, a prerelease of the version the bump leads to is released as is instead
+fn bumped(version: &Version, bump: Bump) -> Version {
+    let mut version = version.clone();
+    // 2.0.0-rc.1 is a prerelease of the next major version already, 1.2.3-rc.1 is not
+    let releases_pre = !version.pre.is_empty()
+        && match bump {
+            Bump::Major => version.minor == 0 && version.patch == 0,
+            Bump::Minor => version.patch == 0,
+            Bump::Patch => true,
+        };
+
+    if !releases_pre {
+        match bump {
+            Bump::Major => {
+                version.major += 1;
+                version.minor = 0;
+                version.patch = 0;
+            }
+            Bump::Minor => {
+                version.minor += 1;
+                version.patch = 0;
+            }
+            Bump::Patch => version.patch += 1,
+        }
+    }
+    version.pre = Prerelease::EMPTY;
+    version.build = BuildMetadata::EMPTY;
+    version
+}
+
+/// Compares the shared folder with the one of the newest published version before this one
+fn suggest_bump(package: &PackageConfig) -> Bump {
+    let repo = MultiDependencyProvider::useful_default_new();
+    let previous = repo
+        .get_versions(&package.info.id)
+        .into_iter()
+        .flatten()
+        .filter(|v| v.version <= package.info.version && !v.yanked.unwrap_or(false))
+        .map(|v| v.version)
+        .max()
+        .unwrap_or_else(|| {
+            panic!(
+                "No published version of {} to compare with, pick the bump yourself",
+                package.info.id.bright_red()
+            )
+        });
+
+    let shared_package = repo
+        .get_shared_package(&package.info.id, &previous)
+        .unwrap_or_else(|| panic!("Could not find package {} {}", package.info.id, previous));
+    let shared_dep =
+        SharedDependency::for_package(package, &package.overrides, shared_package.clone());
+    shared_dep.cache_src(&shared_package);
+
+    let previous_dir = shared_dep
+        .cached_src_path()
+        .join(&shared_package.config.shared_dir);
+    println!(
+        "Comparing {} with {} {}",
+        package.shared_dir.display().bright_yellow(),
+        package.info.id.bright_red(),
+        previous.bright_green()
+    );
+
+    let previous_files = collect_files(&previous_dir);
+    let current_files = collect_files(&package.shared_dir);
+
+    let mut bump = Bump::Patch;
+    for file in previous_files.iter() {
+        if !current_files.contains(file) {
+            println!(" - removed {}", file.display().bright_red());
+            bump = Bump::Major;
+            continue;
+        }
+
+        let old = declarations(&previous_dir.join(file));
+        let new = declarations(&package.shared_dir.join(file));
+        if old.iter().any(|declaration| !new.contains(declaration)) {
+            println!(" - changed {}", file.display().bright_red());
+            bump = Bump::Major;
+        } else if new.len() > old.len() {
+            println!(" - extended {}", file.display().bright_green());
+            bump = bump.max(Bump::Minor);
+        }
+    }
+
+    for file in current_files.difference(&previous_files) {
+        println!(" - added {}", file.display().bright_green());
+        bump = bump.max(Bump::Minor);
+    }
+
+    println!("Suggested bump: {}", format!("{bump:?}").bright_yellow());
+    bump
+}
+
+/// All files in a folder, relative to it
+fn collect_files(dir: &Path) -> HashSet<PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path().strip_prefix(dir).unwrap().to_path_buf())
+        .collect()
+}
+
+/// The lines of a header without comments and whitespace, if any of them is gone the header changed signatures
+fn declarations(path: &Path) -> HashSet<String> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+
+    let mut result = HashSet::new();
+    let mut in_comment = false;
+    for line in content.lines() {
+        let mut line = line;
+        let mut code = String::new();
+        loop {
+            if in_comment {
+                match line.find("*/") {
+                    Some(end) => {
+                        line = &line[end + 2..];
+                        in_comment = false;
+                    }
+                    None => break,
+                }
+            } else if let Some(start) = line.find("/*") {
+                code.push_str(&line[..start]);
+                line = &line[start + 2..];
+                in_comment = true;
+            } else {
+                code.push_str(line);
+                break;
+            }
+        }
+
+        if let Some(start) = code.find("//") {
+            code.truncate(start);
+        }
+
+        let code: String = code.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !code.is_empty() {
+            result.insert(code);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::{bumped, Bump};
+
+    fn bump(version: &str, bump: Bump) -> String {
+        bumped(&Version::parse(version).unwrap(), bump).to_string()
+    }
+
+    #[test]
+    fn increments_the_bumped_part() {
+        assert_eq!(bump("1.2.3", Bump::Major), "2.0.0");
+        assert_eq!(bump("1.2.3", Bump::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3+build.1", Bump::Patch), "1.2.4");
+    }
+
+    #[test]
+    fn releases_prereleases_of_the_bumped_version() {
+        assert_eq!(bump("2.0.0-rc.1", Bump::Major), "2.0.0");
+        assert_eq!(bump("1.3.0-rc.1", Bump::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3-rc.1", Bump::Patch), "1.2.3");
+    }
+
+    #[test]
+    fn increments_prereleases_of_a_smaller_version() {
+        assert_eq!(bump("1.2.3-rc.1", Bump::Major), "2.0.0");
+        assert_eq!(bump("1.2.3-rc.1", Bump::Minor), "1.3.0");
+        assert_eq!(bump("1.2.0-rc.1", Bump::Major), "2.0.0");
+    }
+}
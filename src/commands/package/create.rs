@@ -2,11 +2,11 @@ use std::path::Path;
 
 use clap::{Args};
 use owo_colors::OwoColorize;
-use semver::Version;
+use semver::{Version, VersionReq};
 
 use crate::data::{
     dependency::{AdditionalDependencyData, Dependency},
-    package::{AdditionalPackageData, PackageConfig, PackageInfo},
+    package::{AdditionalPackageData, GameTarget, PackageConfig, PackageInfo},
 };
 #[derive(Args, Debug, Clone)]
 
@@ -36,6 +36,12 @@ pub struct PackageOperationCreateArgs {
     /// Override the downloaded .so or .a filename with this name instead.
     #[clap(long = "overrideSoName")]
     pub override_so_name: Option<String>,
+    /// Specify the id of the game this package is made for
+    #[clap(long = "gameId")]
+    pub game_id: Option<String>,
+    /// Specify which versions of the game this package works with, any version if not given
+    #[clap(long = "gameVersion")]
+    pub game_version: Option<VersionReq>,
 }
 
 pub fn package_create_operation(create_parameters: PackageOperationCreateArgs) {
@@ -68,7 +74,12 @@ pub fn package_create_operation(create_parameters: PackageOperationCreateArgs) {
         id,
         name: create_parameters.name,
         url: None,
+        description: None,
         version: create_parameters.version,
+        game: create_parameters.game_id.map(|id| GameTarget {
+            id,
+            version_range: create_parameters.game_version.unwrap_or(VersionReq::STAR),
+        }),
         additional_data,
     };
 
@@ -77,7 +88,11 @@ pub fn package_create_operation(create_parameters: PackageOperationCreateArgs) {
         shared_dir: Path::new("shared").to_owned(),
         dependencies_dir: Path::new("extern").to_owned(),
         dependencies: Vec::<Dependency>::default(),
+        dev_dependencies: Vec::<Dependency>::default(),
         additional_data: AdditionalDependencyData::default(),
+        features: Default::default(),
+        overrides: Default::default(),
+        targets: Default::default(),
     };
 
     package.write();
@@ -1,7 +1,7 @@
 use clap::{Args};
 use semver::Version;
 
-use crate::data::{package::{PackageConfig, SharedPackageConfig}, repo::DependencyRepository};
+use crate::data::{package::{PackageConfig, SharedPackageConfig}, repo::multi_provider::MultiDependencyProvider};
 
 #[derive(Args, Debug, Clone)]
 
@@ -18,9 +18,12 @@ pub struct EditArgs {
     ///Edit the version property of the package
     #[clap(long)]
     pub version: Option<Version>,
+    ///Edit the description property of the package
+    #[clap(long)]
+    pub description: Option<String>,
 }
 
-pub fn package_edit_operation(edit_parameters: EditArgs, repo: &impl DependencyRepository) {
+pub fn package_edit_operation(edit_parameters: EditArgs) {
     let mut package = PackageConfig::read();
     let mut any_changed = false;
     if let Some(id) = edit_parameters.id {
@@ -39,17 +42,31 @@ pub fn package_edit_operation(edit_parameters: EditArgs, repo: &impl DependencyR
         package_set_version(&mut package, version);
         any_changed = true;
     }
+    if let Some(description) = edit_parameters.description {
+        package_set_description(&mut package, description);
+        any_changed = true;
+    }
 
     if any_changed {
-        package.write();
-        let mut shared_package = SharedPackageConfig::read();
-        shared_package.config = package;
-        shared_package.write();
+        write_edited_package(package);
+    }
+}
 
-        // HACK: Not sure if this is a proper way of doing this but it seems logical
-        shared_package.write_define_cmake();
-        shared_package.write_extern_cmake(repo);
+/// Writes the package, and updates the shared package and cmake files that depend on it once it has been restored
+pub fn write_edited_package(package: PackageConfig) {
+    package.write();
+    if !SharedPackageConfig::check() {
+        return;
     }
+
+    let mut shared_package = SharedPackageConfig::read();
+    let repo = MultiDependencyProvider::for_shared_package(&shared_package);
+    shared_package.config = package;
+    shared_package.write();
+
+    // HACK: Not sure if this is a proper way of doing this but it seems logical
+    shared_package.write_define_cmake(&shared_package.config.get_target(None));
+    shared_package.write_extern_cmake(&repo);
 }
 
 fn package_set_id(package: &mut PackageConfig, id: String) {
@@ -67,7 +84,12 @@ fn package_set_url(package: &mut PackageConfig, url: String) {
     package.info.url = Option::Some(url);
 }
 
-fn package_set_version(package: &mut PackageConfig, version: Version) {
+fn package_set_description(package: &mut PackageConfig, description: String) {
+    println!("Setting package description: {}", description);
+    package.info.description = Option::Some(description);
+}
+
+pub fn package_set_version(package: &mut PackageConfig, version: Version) {
     println!("Setting package version: {}", version);
     package.info.version = version;
 }
@@ -1,9 +1,8 @@
 use clap::{Args, Subcommand};
 
-use crate::{
-    data::{package::{PackageConfig, SharedPackageConfig}, repo::DependencyRepository},
-    utils::toggle::Toggle,
-};
+use crate::{data::package::PackageConfig, utils::toggle::Toggle};
+
+use super::edit::write_edited_package;
 
 #[derive(Args, Debug, Clone)]
 
@@ -76,7 +75,7 @@ pub struct CompileOptionsEditArgs {
     pub c_flags: Option<String>,
 }
 
-pub fn package_edit_extra_operation(edit_parameters: EditExtraArgs, repo: &impl DependencyRepository) {
+pub fn package_edit_extra_operation(edit_parameters: EditExtraArgs) {
     let mut package = PackageConfig::read();
     let mut any_changed = false;
     if let Some(branch_name) = edit_parameters.branch_name {
@@ -117,14 +116,7 @@ pub fn package_edit_extra_operation(edit_parameters: EditExtraArgs, repo: &impl
     }
 
     if any_changed {
-        package.write();
-        let mut shared_package = SharedPackageConfig::read();
-        shared_package.config = package;
-        shared_package.write();
-
-        // HACK: Not sure if this is a proper way of doing this but it seems logical
-        shared_package.write_define_cmake();
-        shared_package.write_extern_cmake(repo);
+        write_edited_package(package);
     }
 }
 
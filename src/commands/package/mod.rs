@@ -1,8 +1,7 @@
 use clap::{Subcommand, Args};
 
-use crate::data::repo::multi_provider::MultiDependencyProvider;
-
-mod create;
+mod bump;
+pub mod create;
 mod edit;
 mod edit_extra;
 
@@ -22,13 +21,16 @@ pub enum PackageOperation {
     Edit(edit::EditArgs),
     /// Edit extra supported properties of the package
     EditExtra(edit_extra::EditExtraArgs),
+    /// Bump the version of the package
+    Bump(bump::BumpArgs),
 }
 
 pub fn execute_package_operation(operation: Package) {
     match operation.op {
         PackageOperation::Create(c) => create::package_create_operation(c),
-        PackageOperation::Edit(e) => edit::package_edit_operation(e, &MultiDependencyProvider::useful_default_new()),
-        PackageOperation::EditExtra(ee) => edit_extra::package_edit_extra_operation(ee, &MultiDependencyProvider::useful_default_new()),
+        PackageOperation::Edit(e) => edit::package_edit_operation(e),
+        PackageOperation::EditExtra(ee) => edit_extra::package_edit_extra_operation(ee),
+        PackageOperation::Bump(b) => bump::package_bump_operation(b),
     }
 }
 
@@ -0,0 +1,103 @@
+use owo_colors::OwoColorize;
+use serde_json::Value;
+
+use crate::data::{package::SharedPackageConfig, qpackages};
+
+/// Prints what changed compared to the newest version of the package on qpackages that is older than this one
+pub fn print_registry_diff(package: &SharedPackageConfig) {
+    let info = &package.config.info;
+    let previous = qpackages::get_versions(&info.id)
+        .into_iter()
+        .flatten()
+        .map(|v| v.version)
+        .filter(|v| *v < info.version)
+        .max();
+
+    let previous = match previous.and_then(|v| qpackages::get_shared_package(&info.id, &v)) {
+        Some(previous) => previous,
+        None => {
+            println!("No previous version of {} on qpackages", info.id.bright_red());
+            return;
+        }
+    };
+
+    println!(
+        "Changes since {} {}:",
+        info.id.bright_red(),
+        previous.config.info.version.bright_green()
+    );
+
+    let mut any = false;
+    for dependency in package.config.dependencies.iter() {
+        match previous
+            .config
+            .dependencies
+            .iter()
+            .find(|dep| dep.id == dependency.id)
+        {
+            None => {
+                any = true;
+                println!(
+                    " + dependency {} {}",
+                    dependency.id.bright_green(),
+                    dependency.version_range.bright_blue()
+                );
+            }
+            Some(old) if old.version_range != dependency.version_range => {
+                any = true;
+                println!(
+                    " ~ dependency {}: {} -> {}",
+                    dependency.id.bright_yellow(),
+                    old.version_range.bright_blue(),
+                    dependency.version_range.bright_blue()
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    for old in previous.config.dependencies.iter() {
+        if !package
+            .config
+            .dependencies
+            .iter()
+            .any(|dep| dep.id == old.id)
+        {
+            any = true;
+            println!(
+                " - dependency {} {}",
+                old.id.bright_red(),
+                old.version_range.bright_blue()
+            );
+        }
+    }
+
+    let old_data = serde_json::to_value(&previous.config.info.additional_data).unwrap();
+    let new_data = serde_json::to_value(&info.additional_data).unwrap();
+    if let (Value::Object(old_data), Value::Object(new_data)) = (old_data, new_data) {
+        for (key, value) in new_data.iter() {
+            match old_data.get(key) {
+                None => {
+                    any = true;
+                    println!(" + additionalData.{}: {}", key.bright_green(), value);
+                }
+                Some(old) if old != value => {
+                    any = true;
+                    println!(" ~ additionalData.{}: {} -> {}", key.bright_yellow(), old, value);
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (key, value) in old_data.iter() {
+            if !new_data.contains_key(key) {
+                any = true;
+                println!(" - additionalData.{}: {}", key.bright_red(), value);
+            }
+        }
+    }
+
+    if !any {
+        println!(" no dependency or additionalData changes");
+    }
+}
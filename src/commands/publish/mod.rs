@@ -6,18 +6,68 @@ use crate::data::config::get_publish_keyring;
 pub struct Publish {
     /// the authorization header to use for publishing, if present
     pub publish_auth: Option<String>,
+
+    /// Print what would be published and how it differs from the previous version, without publishing
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Upload the built artifacts to the github release of the current tag and use their links
+    #[clap(long)]
+    pub release: bool,
 }
 
 use owo_colors::OwoColorize;
 
-use crate::data::package::SharedPackageConfig;
+mod diff;
+mod preflight;
+mod release;
+
+use crate::data::{package::SharedPackageConfig, workspace::WorkspaceConfig};
 pub fn execute_publish_operation(auth: &Publish) {
-    let package = SharedPackageConfig::read();
+    if WorkspaceConfig::check() {
+        // members come after the members they depend on, so those are already published when they are checked
+        for (member, _) in WorkspaceConfig::read().read_members() {
+            WorkspaceConfig::in_member(&member, || publish_package(auth));
+        }
+        return;
+    }
+
+    publish_package(auth);
+}
+
+fn publish_package(auth: &Publish) {
+    let mut package = SharedPackageConfig::read();
     if package.config.info.url.is_none() {
         println!("Package without url can not be published!");
         return;
     }
 
+    // overrides only apply to this package, consumers would get something else entirely
+    if !package.config.overrides.is_empty()
+        || package
+            .restored_dependencies
+            .iter()
+            .any(|dep| dep.package_override.is_some())
+    {
+        panic!(
+            "Package {} uses overrides, remove them and restore before publishing!",
+            package.config.info.id.bright_red()
+        );
+    }
+
+    // local path dependencies can not be restored by anyone else
+    if let Some(dependency) = package
+        .config
+        .dependencies
+        .iter()
+        .find(|dep| dep.additional_data.local_path.is_some())
+    {
+        panic!(
+            "Dependency {} uses a localPath, remove it before publishing!",
+            dependency.id.bright_red()
+        );
+    }
+
     // check if all dependencies are available off of qpackages
     for dependency in package.config.dependencies.iter() {
         match dependency.get_shared_package() {
@@ -59,19 +109,69 @@ pub fn execute_publish_operation(auth: &Publish) {
     if package.config.info.url.is_none() {
         panic!("info.url is null, please make sure to init this with the base link to your repo, e.g. '{}'", "https://github.com/RedBrumbler/QuestPackageManager-Rust".bright_yellow());
     }
-    // check if this is header only, if it's not header only check if the so_link is set, if not, panic
-    if !package
-        .config
-        .info
-        .additional_data
-        .headers_only
-        .unwrap_or(false)
-        && package.config.info.additional_data.so_link.is_none()
+    // check if this is header only, if it's not header only check if the so_link is set for every target, if not, panic
+    // a release sets the links from the uploaded binaries
+    if !auth.release
+        && !package
+            .config
+            .info
+            .additional_data
+            .headers_only
+            .unwrap_or(false)
     {
-        panic!("soLink is not set in the package config, but this package is not header only, please make sure to either add the soLink or to make the package header only.");
+        for target in package.config.get_targets() {
+            if package
+                .config
+                .info
+                .additional_data
+                .get_so_link(&target.abi)
+                .is_none()
+            {
+                panic!("soLink is not set in the package config for target {}, but this package is not header only, please make sure to either add the soLink or to make the package header only.", target.abi.bright_red());
+            }
+        }
+    }
+
+    // make sure consumers can actually get everything the package points to
+    preflight::check_remote(&package, auth.dry_run);
+
+    // only upload once nothing else can stop the publish
+    if auth.release {
+        if auth.dry_run {
+            println!("Dry run, the release artifacts are not uploaded, the links in the preview are replaced by theirs when publishing");
+        } else {
+            release::upload_release(&mut package);
+        }
+    }
+
+    // the links are final now, the ones a dry run would replace are not worth checking
+    if !(auth.release && auth.dry_run) {
+        preflight::check_links(&package, auth.dry_run);
+    }
+
+    // workspace members are restored from their folder locally, consumers get them from qpackages
+    for restored in package.restored_dependencies.iter_mut() {
+        restored.dependency.additional_data.local_path = None;
     }
 
-    // TODO: Implement a check that gets the repo and checks if the shared folder and subfolder exists, if not it throws an error and won't let you publish
+    // dev dependencies are only for developing this package
+    package.config.dev_dependencies.clear();
+    package
+        .restored_dependencies
+        .retain(|dep| !dep.dev.unwrap_or(false));
+
+    if auth.dry_run {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&package).expect("Serialization failed")
+        );
+        diff::print_registry_diff(&package);
+        println!(
+            "Dry run, package {} v{} was not published",
+            package.config.info.id, package.config.info.version
+        );
+        return;
+    }
 
     if let Some(key) = &auth.publish_auth {
         package.publish(&key);
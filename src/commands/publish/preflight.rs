@@ -0,0 +1,176 @@
+use std::{io::Cursor, path::Path};
+
+use owo_colors::OwoColorize;
+use remove_dir_all::remove_dir_all;
+use zip::ZipArchive;
+
+use crate::{
+    data::{
+        config::get_keyring,
+        package::{PackageConfig, SharedPackageConfig},
+    },
+    utils::{git, network::get_agent},
+};
+
+/// Fetches the source the package points to and checks that consumers will find everything there,
+/// refuses to publish when anything is missing, a dry run only warns
+pub fn check_remote(package: &SharedPackageConfig, dry_run: bool) {
+    let info = &package.config.info;
+    let url = info.url.as_ref().expect("info.url is null");
+
+    let tmp_path = std::env::temp_dir().join(format!("qpm-publish-{}-{}", info.id, info.version));
+    if tmp_path.exists() {
+        remove_dir_all(&tmp_path).expect("Failed to remove existing tmp folder");
+    }
+
+    println!("Fetching {} to check its contents", url.bright_yellow());
+    if url.contains("github.com") {
+        // branch_name may also be a tag, git clone -b accepts both
+        // without one the tag of this version is what consumers should get, not whatever the default branch has now
+        let branch = info.additional_data.branch_name.clone().or_else(|| {
+            let tag = [format!("v{}", info.version), info.version.to_string()]
+                .into_iter()
+                .find(|tag| git::remote_has_tag(url.clone(), tag));
+            if tag.is_none() {
+                println!(
+                    "{} no branchName and no tag v{} or {} in the repository, checking the default branch",
+                    "Warning:".bright_yellow(),
+                    info.version,
+                    info.version
+                );
+            }
+            tag
+        });
+        git::clone(url.clone(), branch.as_ref(), &tmp_path);
+    } else {
+        let response = get_agent()
+            .get(url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .unwrap_or_else(|e| panic!("Failed to download {}: {}", url, e));
+        let buffer = Cursor::new(response.bytes().unwrap());
+        ZipArchive::new(buffer)
+            .expect("info.url is not a github repository or a zip file")
+            .extract(&tmp_path)
+            .expect("Failed to extract zip");
+    }
+
+    let problems = check_source(&package.config, &tmp_path);
+    remove_dir_all(&tmp_path).expect("Failed to remove tmp folder");
+
+    report(&info.id, &problems, dry_run);
+}
+
+/// Checks that every download link of the package works, run once the links are final
+pub fn check_links(package: &SharedPackageConfig, dry_run: bool) {
+    let info = &package.config.info;
+    let mut problems = Vec::new();
+
+    for (name, link) in [
+        ("soLink", &info.additional_data.so_link),
+        ("debugSoLink", &info.additional_data.debug_so_link),
+        ("modLink", &info.additional_data.mod_link),
+    ] {
+        if let Some(link) = link {
+            if let Some(problem) = check_link(link) {
+                problems.push(format!("{name} {link} {problem}"));
+            }
+        }
+    }
+
+    for (abi, links) in info.additional_data.target_links.iter().flatten() {
+        for (name, link) in [("soLink", &links.so_link), ("debugSoLink", &links.debug_so_link)] {
+            if let Some(link) = link {
+                if let Some(problem) = check_link(link) {
+                    problems.push(format!("{name} of {abi} {link} {problem}"));
+                }
+            }
+        }
+    }
+
+    report(&info.id, &problems, dry_run);
+}
+
+/// Refuses to publish when there are problems, a dry run only shows them as warnings
+fn report(id: &str, problems: &[String], dry_run: bool) {
+    if problems.is_empty() {
+        return;
+    }
+
+    if dry_run {
+        for problem in problems.iter() {
+            println!("{} {}", "Warning:".bright_yellow(), problem);
+        }
+        return;
+    }
+
+    for problem in problems.iter() {
+        eprintln!(" - {}", problem.bright_red());
+    }
+    panic!(
+        "Package {} can not be published until these are fixed",
+        id.bright_red()
+    );
+}
+
+/// Checks the fetched source for everything a consumer needs to restore the package
+fn check_source(package: &PackageConfig, source: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let root = match &package.info.additional_data.sub_folder {
+        Some(sub_folder) => {
+            let root = source.join(sub_folder);
+            if !root.exists() {
+                problems.push(format!("subFolder {sub_folder} does not exist in the repository"));
+                return problems;
+            }
+            root
+        }
+        None => source.to_path_buf(),
+    };
+
+    let package_path = root.join("qpm.json");
+    if package_path.exists() {
+        let remote = PackageConfig::read_path(package_path);
+        if remote.info.version != package.info.version {
+            problems.push(format!(
+                "the qpm.json in the repository has version {}, but {} is being published",
+                remote.info.version, package.info.version
+            ));
+        }
+    } else {
+        problems.push("qpm.json does not exist in the repository".to_string());
+    }
+
+    if !root.join(&package.shared_dir).exists() {
+        problems.push(format!(
+            "shared folder {} does not exist in the repository",
+            package.shared_dir.display()
+        ));
+    }
+
+    for extra_file in package.info.additional_data.extra_files.iter().flatten() {
+        if !root.join(extra_file).exists() {
+            problems.push(format!("extra file {extra_file} does not exist in the repository"));
+        }
+    }
+
+    problems
+}
+
+/// Checks that a download link works, returns what is wrong with it otherwise
+fn check_link(link: &str) -> Option<String> {
+    let mut request = get_agent().head(link);
+    // releases of private repositories need the token, like when they are downloaded
+    if link.contains("github.com") {
+        if let Ok(token) = get_keyring().get_password() {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+    }
+
+    match request.send() {
+        Ok(response) if response.status().is_success() => None,
+        Ok(response) => Some(format!("responded with {}", response.status())),
+        Err(e) => Some(format!("could not be reached: {e}")),
+    }
+}
@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use owo_colors::OwoColorize;
+
+use crate::{
+    data::{
+        config::get_keyring,
+        package::{BuildTarget, PackageConfig, SharedPackageConfig, TargetLinks},
+    },
+    utils::github::{parse_repo_url, GithubClient, GITHUB_API_URL},
+};
+
+/// Uploads the built artifacts of every target to the github release of the current tag, and points the package links at them
+pub fn upload_release(package: &mut SharedPackageConfig) {
+    let url = package
+        .config
+        .info
+        .url
+        .as_ref()
+        .expect("info.url is null, releases are made on the github repository it points to");
+    let (owner, repo) = parse_repo_url(url)
+        .unwrap_or_else(|| panic!("info.url {} is not a github repository", url.bright_yellow()));
+
+    let tag = current_tag();
+    let token = get_keyring().get_password().unwrap_or_else(|_| {
+        panic!(
+            "No github token stored, set one with {}",
+            "qpm config token".bright_yellow()
+        )
+    });
+    let client = GithubClient::new(GITHUB_API_URL, &token);
+    let release = client.get_or_create_release(&owner, &repo, &tag);
+
+    let so_name = package.config.get_so_name();
+    let targets = package.config.get_targets();
+    let build_dirs: Vec<PathBuf> = targets
+        .iter()
+        .map(|target| package.config.get_build_dir(target))
+        .collect();
+    // the qmod is packed for the first target, next to its binaries
+    let qmod_path = build_dirs[0].join(package.config.get_qmod_name());
+    let has_targets = !package.config.targets.is_empty();
+
+    let additional_data = &mut package.config.info.additional_data;
+    if !additional_data.headers_only.unwrap_or(false) {
+        // every binary has the same file name, so the assets are named after their abi
+        for (target, build_dir) in targets.iter().zip(build_dirs.iter()) {
+            let so_path = build_dir.join(&so_name);
+            let debug_so_path = build_dir.join("debug").join(&so_name);
+            let asset_name = if target.abi == BuildTarget::DEFAULT_ABI {
+                so_name.clone()
+            } else {
+                format!("{}_{}", target.abi, so_name)
+            };
+
+            if !so_path.exists() {
+                panic!(
+                    "Could not find binary {} for {}, build the package first",
+                    so_path.display().bright_red(),
+                    target.abi.bright_red()
+                );
+            }
+            let links = TargetLinks {
+                so_link: Some(client.upload_asset_as(
+                    &owner,
+                    &repo,
+                    &release,
+                    &so_path,
+                    &asset_name,
+                )),
+                debug_so_link: if debug_so_path.exists() {
+                    Some(client.upload_asset_as(
+                        &owner,
+                        &repo,
+                        &release,
+                        &debug_so_path,
+                        &format!("debug_{}", asset_name),
+                    ))
+                } else {
+                    println!("Could not find debug binary {debug_so_path:?}, skipping");
+                    None
+                },
+            };
+
+            // soLink and debugSoLink stay the links of the default abi, for versions of qpm without targets
+            if target.abi == BuildTarget::DEFAULT_ABI {
+                additional_data.so_link = links.so_link.clone();
+                additional_data.debug_so_link = links.debug_so_link.clone();
+            }
+            if has_targets {
+                additional_data
+                    .target_links
+                    .get_or_insert_with(Default::default)
+                    .insert(target.abi.clone(), links);
+            }
+        }
+    }
+
+    if qmod_path.exists() {
+        additional_data.mod_link = Some(client.upload_asset(&owner, &repo, &release, &qmod_path));
+    }
+
+    // the links belong to the package now, keep them around for the next restore and publish
+    // qpm.json may have changed since the last restore, so only the links are written to it
+    let links = &package.config.info.additional_data;
+    let mut config = PackageConfig::read();
+    config.info.additional_data.so_link = links.so_link.clone();
+    config.info.additional_data.debug_so_link = links.debug_so_link.clone();
+    config.info.additional_data.target_links = links.target_links.clone();
+    config.info.additional_data.mod_link = links.mod_link.clone();
+    config.write();
+    package.write();
+}
+
+/// The tag that points at the current commit
+fn current_tag() -> String {
+    let output = std::process::Command::new("git")
+        .args(["describe", "--tags", "--exact-match", "HEAD"])
+        .output()
+        .expect("Failed to run git");
+
+    if !output.status.success() {
+        panic!("The current commit is not tagged, tag it before releasing!");
+    }
+
+    String::from_utf8(output.stdout)
+        .expect("Tag is not valid utf8")
+        .trim()
+        .to_string()
+}
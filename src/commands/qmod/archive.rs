@@ -0,0 +1,141 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::data::{mod_json::ModJson, package::PackageConfig};
+
+#[derive(Args, Debug, Clone)]
+pub struct ZipQmodOperationArgs {
+    /// Where to write the qmod, defaults to the qmod name of the package in the build folder
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+
+    /// The abi of the build target to pack the libraries of, defaults to the first target of the package
+    #[clap(long)]
+    pub target: Option<String>,
+}
+
+/// Packs the generated mod.json and every file it refers to into a qmod
+pub fn execute_qmod_zip_operation(zip_parameters: ZipQmodOperationArgs) {
+    assert!(
+        Path::new(ModJson::get_result_name()).exists(),
+        "No mod.json found in the current directory, generate it first with \"qpm qmod build\""
+    );
+
+    let package = PackageConfig::read();
+    let mod_json = ModJson::read(PathBuf::from(ModJson::get_result_name()));
+    let target = package.get_target(zip_parameters.target.as_deref());
+    let build_dir = package.get_build_dir(&target);
+    let files = qmod_files(&build_dir, &package.get_libs_dir(&target), &mod_json);
+
+    let output = zip_parameters
+        .output
+        .unwrap_or_else(|| build_dir.join(package.get_qmod_name()));
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).expect("Failed to create output folder");
+    }
+
+    let mut zip = ZipWriter::new(File::create(&output).expect("Failed to create qmod"));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(ModJson::get_result_name(), options)
+        .expect("Failed to add mod.json to qmod");
+    zip.write_all(&std::fs::read(ModJson::get_result_name()).expect("Failed to read mod.json"))
+        .expect("Failed to add mod.json to qmod");
+
+    for (name, path) in files.iter() {
+        zip.start_file(name, options)
+            .unwrap_or_else(|_| panic!("Failed to add {} to qmod", name.bright_red()));
+        let mut file = File::open(path)
+            .unwrap_or_else(|_| panic!("Failed to open {}", path.display().bright_red()));
+        std::io::copy(&mut file, &mut zip)
+            .unwrap_or_else(|_| panic!("Failed to add {} to qmod", name.bright_red()));
+    }
+    zip.finish().expect("Failed to write qmod");
+
+    println!(
+        "Wrote {} with {} files",
+        output.display().bright_green(),
+        files.len() + 1
+    );
+}
+
+/// The name in the qmod and the path on disk of every file the mod.json refers to, panics if any of them is missing
+fn qmod_files(build_dir: &Path, libs_dir: &Path, mod_json: &ModJson) -> Vec<(String, PathBuf)> {
+    let (files, missing) = locate_files(build_dir, libs_dir, mod_json);
+
+    if !missing.is_empty() {
+        for name in missing.iter() {
+            eprintln!(" - {}", name.bright_red());
+        }
+        panic!(
+            "{} files referenced by mod.json are missing, build and restore first",
+            missing.len()
+        );
+    }
+
+    files
+}
+
+/// Finds the files the mod.json refers to on disk, and describes the ones that could not be found
+pub(super) fn locate_files(
+    build_dir: &Path,
+    libs_dir: &Path,
+    mod_json: &ModJson,
+) -> (Vec<(String, PathBuf)>, Vec<String>) {
+    let mut files = Vec::new();
+    let mut missing = Vec::new();
+
+    // libraries are either built by this package, or restored from a dependency
+    for name in mod_json.mod_files.iter().chain(mod_json.library_files.iter()) {
+        match [build_dir.join(name), libs_dir.join(name)]
+            .into_iter()
+            .find(|path| path.exists())
+        {
+            Some(path) => files.push((name.clone(), path)),
+            None => missing.push(format!(
+                "{} (looked in {} and {})",
+                name,
+                build_dir.display(),
+                libs_dir.display()
+            )),
+        }
+    }
+
+    // file copies and the cover image are relative to the package
+    let others = mod_json
+        .file_copies
+        .iter()
+        .map(|copy| &copy.name)
+        .chain(mod_json.cover_image.iter());
+    for name in others {
+        let path = PathBuf::from(name);
+        if path.exists() {
+            files.push((name.clone(), path));
+        } else {
+            missing.push(name.clone());
+        }
+    }
+
+    // a library can be both built and listed by a dependency, it only goes in once
+    let mut seen = HashSet::new();
+    files.retain(|(name, _)| seen.insert(name.clone()));
+    (files, missing)
+}
+
+/// The names of all files the mod.json expects to be in the qmod, besides the mod.json itself
+pub(super) fn packaged_files(mod_json: &ModJson) -> impl Iterator<Item = &String> {
+    mod_json
+        .mod_files
+        .iter()
+        .chain(mod_json.library_files.iter())
+        .chain(mod_json.file_copies.iter().map(|copy| &copy.name))
+        .chain(mod_json.cover_image.iter())
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use semver::Version;
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::data::{
+    mod_json::ModJson,
+    package::{BuildTarget, PackageConfig, SharedPackageConfig},
+};
+
+use super::archive;
+
+/// The mod.json schema versions mod loaders understand
+const SCHEMA_VERSIONS: &[&str] = &["0.1.0", "0.1.1", "0.1.2", "1.0.0", "1.1.0"];
+
+/// Fields that were added after the first schema version, with the version that added them
+const FIELDS_SINCE: &[(&str, &str)] = &[
+    ("porter", "0.1.1"),
+    ("isLibrary", "0.1.2"),
+    ("copyExtensions", "1.0.0"),
+];
+
+#[derive(Args, Debug, Clone)]
+pub struct CheckQmodOperationArgs {
+    /// The mod.json or .qmod to check, defaults to the mod.json in the current directory
+    pub file: Option<PathBuf>,
+}
+
+/// What a check found, errors make the mod unusable while warnings are probably mistakes
+#[derive(Default)]
+struct Findings {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+pub fn execute_qmod_check_operation(check_parameters: CheckQmodOperationArgs) {
+    let path = check_parameters
+        .file
+        .unwrap_or_else(|| PathBuf::from(ModJson::get_result_name()));
+    let mut findings = Findings::default();
+
+    let is_qmod = path.extension().map(|ext| ext == "qmod").unwrap_or(false);
+    let (raw, archive_files) = if is_qmod {
+        let (raw, files) = read_qmod(&path);
+        (raw, Some(files))
+    } else {
+        let raw = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Failed to read {}", path.display().bright_red()));
+        (raw, None)
+    };
+
+    let value: Value = serde_json::from_str(&raw)
+        .unwrap_or_else(|e| panic!("{} is not valid json: {}", path.display().bright_red(), e));
+    check_schema(&value, &mut findings);
+
+    // the rest needs the fields to be usable
+    match serde_json::from_value::<ModJson>(value) {
+        Ok(mod_json) => {
+            check_version(&mod_json, &mut findings);
+            check_files(&mod_json, archive_files.as_ref(), &mut findings);
+            check_dependencies(&mod_json, &mut findings);
+        }
+        Err(e) => findings.errors.push(format!("mod.json can not be read: {}", e)),
+    }
+
+    for warning in findings.warnings.iter() {
+        println!("{} {}", "warning:".bright_yellow(), warning);
+    }
+    for error in findings.errors.iter() {
+        println!("{} {}", "error:".bright_red(), error);
+    }
+
+    if !findings.errors.is_empty() {
+        println!(
+            "{} has {} errors and {} warnings",
+            path.display().bright_red(),
+            findings.errors.len(),
+            findings.warnings.len()
+        );
+        std::process::exit(1);
+    }
+    println!(
+        "{} is valid, with {} warnings",
+        path.display().bright_green(),
+        findings.warnings.len()
+    );
+}
+
+/// The mod.json inside a qmod and the names of all files in it
+fn read_qmod(path: &Path) -> (String, HashSet<String>) {
+    let file = File::open(path)
+        .unwrap_or_else(|_| panic!("Failed to open {}", path.display().bright_red()));
+    let mut zip = ZipArchive::new(file)
+        .unwrap_or_else(|_| panic!("{} is not a zip file", path.display().bright_red()));
+    let files = zip.file_names().map(str::to_string).collect();
+
+    let mut raw = String::new();
+    zip.by_name(ModJson::get_result_name())
+        .unwrap_or_else(|_| panic!("{} does not contain a mod.json", path.display().bright_red()))
+        .read_to_string(&mut raw)
+        .expect("Failed to read mod.json");
+    (raw, files)
+}
+
+/// Checks the json itself against what the schema version in _QPVersion allows
+fn check_schema(value: &Value, findings: &mut Findings) {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => {
+            findings.errors.push("mod.json is not a json object".to_string());
+            return;
+        }
+    };
+
+    let schema_version = match object.get("_QPVersion").and_then(Value::as_str) {
+        Some(version) if SCHEMA_VERSIONS.contains(&version) => Version::parse(version).unwrap(),
+        Some(version) => {
+            findings.errors.push(format!(
+                "_QPVersion {} is not a known schema version, known are {}",
+                version,
+                SCHEMA_VERSIONS.join(", ")
+            ));
+            return;
+        }
+        None => {
+            findings.errors.push("_QPVersion is missing".to_string());
+            return;
+        }
+    };
+
+    let mut required = vec!["name", "id", "version", "author"];
+    // packageId became optional with 1.0.0
+    if schema_version < Version::new(1, 0, 0) {
+        required.push("packageId");
+    }
+    for field in required {
+        if !object.contains_key(field) {
+            findings.errors.push(format!("{} is required", field));
+        }
+    }
+
+    for (field, value) in object.iter() {
+        // an empty list means the same as a missing one, older mod loaders ignore it
+        let is_empty = value.as_array().map_or(false, |items| items.is_empty());
+        if let Some((_, since)) = FIELDS_SINCE.iter().find(|(name, _)| *name == field.as_str()) {
+            if !is_empty && schema_version < Version::parse(since).unwrap() {
+                findings.errors.push(format!(
+                    "{} needs _QPVersion {} or newer, this is {}",
+                    field, since, schema_version
+                ));
+            }
+        }
+
+        let valid = match field.as_str() {
+            "_QPVersion" | "name" | "id" | "author" | "porter" | "version" | "packageId"
+            | "packageVersion" | "description" | "coverImage" => value.is_string(),
+            "isLibrary" => value.is_boolean(),
+            "modFiles" | "libraryFiles" => is_array_of(value, |item| item.is_string()),
+            "dependencies" => is_array_of(value, |item| {
+                has_strings(item, &["id", "version"], &["downloadIfMissing"])
+            }),
+            "fileCopies" => is_array_of(value, |item| has_strings(item, &["name", "destination"], &[])),
+            "copyExtensions" => {
+                is_array_of(value, |item| has_strings(item, &["extension", "destination"], &[]))
+            }
+            _ => {
+                findings
+                    .warnings
+                    .push(format!("{} is not part of the schema and will be ignored", field));
+                true
+            }
+        };
+        if !valid {
+            findings.errors.push(format!("{} has the wrong type", field));
+        }
+    }
+}
+
+fn is_array_of(value: &Value, check: impl Fn(&Value) -> bool) -> bool {
+    value
+        .as_array()
+        .map(|items| items.iter().all(check))
+        .unwrap_or(false)
+}
+
+/// Whether the value is an object with the required string fields, and the optional ones are strings when present
+fn has_strings(value: &Value, required: &[&str], optional: &[&str]) -> bool {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => return false,
+    };
+    required
+        .iter()
+        .all(|field| object.get(*field).map(Value::is_string).unwrap_or(false))
+        && optional
+            .iter()
+            .all(|field| object.get(*field).map(Value::is_string).unwrap_or(true))
+}
+
+fn check_version(mod_json: &ModJson, findings: &mut Findings) {
+    if Version::parse(&mod_json.version).is_err() {
+        findings
+            .errors
+            .push(format!("version {} is not a valid semver version", mod_json.version));
+    }
+}
+
+/// Checks that every file the mod.json refers to is in the qmod, or would be found when zipping it
+fn check_files(mod_json: &ModJson, archive_files: Option<&HashSet<String>>, findings: &mut Findings) {
+    match archive_files {
+        Some(files) => {
+            for name in archive::packaged_files(mod_json) {
+                if !files.contains(name) {
+                    findings.errors.push(format!("{} is not in the qmod", name));
+                }
+            }
+        }
+        None => {
+            let (build_dir, libs_dir) = if PackageConfig::check() {
+                let package = PackageConfig::read();
+                let target = package.get_target(None);
+                (package.get_build_dir(&target), package.get_libs_dir(&target))
+            } else {
+                (
+                    PathBuf::from("build"),
+                    Path::new("extern").join("libs").join(BuildTarget::DEFAULT_ABI),
+                )
+            };
+            let (_, missing) = archive::locate_files(&build_dir, &libs_dir, mod_json);
+            for name in missing {
+                findings.errors.push(format!("{} does not exist", name));
+            }
+        }
+    }
+}
+
+/// Checks the dependencies can be installed, and that they agree with what was restored
+fn check_dependencies(mod_json: &ModJson, findings: &mut Findings) {
+    for dependency in mod_json.dependencies.iter() {
+        if dependency.mod_link.is_none() {
+            findings.warnings.push(format!(
+                "dependency {} has no downloadIfMissing, it has to be installed by hand",
+                dependency.id
+            ));
+        }
+    }
+
+    if !Path::new("qpm.shared.json").exists() {
+        return;
+    }
+
+    let shared_package = SharedPackageConfig::read();
+    for dependency in mod_json.dependencies.iter() {
+        match shared_package
+            .restored_dependencies
+            .iter()
+            .find(|restored| restored.dependency.id == dependency.id)
+        {
+            Some(restored) if !dependency.version_range.matches(&restored.version) => {
+                findings.warnings.push(format!(
+                    "dependency {} wants {}, but {} is restored in qpm.shared.json",
+                    dependency.id, dependency.version_range, restored.version
+                ))
+            }
+            Some(_) => {}
+            None => findings.warnings.push(format!(
+                "dependency {} is not restored in qpm.shared.json",
+                dependency.id
+            )),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use zip::ZipArchive;
+
+use crate::{
+    data::mod_json::ModJson,
+    utils::elf::{self, SharedObjectInfo},
+};
+
+#[derive(Args, Debug, Clone)]
+pub struct InspectQmodOperationArgs {
+    /// The qmod to inspect
+    pub file: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExtractQmodOperationArgs {
+    /// The qmod to extract
+    pub file: PathBuf,
+
+    /// Folder to extract to, defaults to the name of the qmod without extension
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn execute_qmod_inspect_operation(inspect_parameters: InspectQmodOperationArgs) {
+    let path = &inspect_parameters.file;
+    let file = File::open(path)
+        .unwrap_or_else(|_| panic!("Failed to open {}", path.display().bright_red()));
+    let mut zip = ZipArchive::new(file)
+        .unwrap_or_else(|_| panic!("{} is not a zip file", path.display().bright_red()));
+
+    let mut mod_json: Option<ModJson> = None;
+    let mut files: Vec<(String, u64)> = Vec::new();
+    let mut shared_objects: HashMap<String, SharedObjectInfo> = HashMap::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).expect("Failed to read qmod entry");
+        let name = entry.name().to_string();
+        files.push((name.clone(), entry.size()));
+
+        if name == ModJson::get_result_name() {
+            mod_json = Some(
+                serde_json::from_reader(&mut entry)
+                    .unwrap_or_else(|e| panic!("mod.json in the qmod can not be read: {}", e)),
+            );
+        } else if name.ends_with(".so") {
+            let mut bytes = Vec::new();
+            entry
+                .read_to_end(&mut bytes)
+                .unwrap_or_else(|_| panic!("Failed to read {}", name.bright_red()));
+            match elf::read_shared_object(&bytes) {
+                Some(info) => {
+                    shared_objects.insert(name, info);
+                }
+                None => println!("{} {} is not an elf file", "warning:".bright_yellow(), name),
+            }
+        }
+    }
+
+    let mod_json = mod_json
+        .unwrap_or_else(|| panic!("{} does not contain a mod.json", path.display().bright_red()));
+
+    println!(
+        "{} ({}) v{} by {}",
+        mod_json.name.bright_green(),
+        mod_json.id,
+        mod_json.version,
+        mod_json.author
+    );
+    if let Some(porter) = &mod_json.porter {
+        println!("ported by {}", porter);
+    }
+    if let Some(package_id) = &mod_json.package_id {
+        println!(
+            "for {} {}",
+            package_id,
+            mod_json.package_version.as_deref().unwrap_or("any version")
+        );
+    }
+    println!("schema version {}", mod_json.schema_version);
+    if mod_json.is_library.unwrap_or(false) {
+        println!("library mod");
+    }
+
+    println!("\ndependencies:");
+    for dependency in mod_json.dependencies.iter() {
+        println!(
+            " - {} {}{}",
+            dependency.id.bright_green(),
+            dependency.version_range,
+            if dependency.mod_link.is_some() {
+                ""
+            } else {
+                " (no downloadIfMissing)"
+            }
+        );
+    }
+
+    println!("\nfiles:");
+    files.sort();
+    for (name, size) in files.iter() {
+        println!(" - {} {}", name, format_size(*size).bright_black());
+    }
+
+    let mut names: Vec<&String> = shared_objects.keys().collect();
+    names.sort();
+    for name in names {
+        let info = &shared_objects[name];
+        println!(
+            "\n{} (SONAME {})",
+            name.bright_green(),
+            info.soname.as_deref().unwrap_or("none")
+        );
+        for needed in info.needed.iter() {
+            println!(" needs {}", needed);
+        }
+    }
+
+    let problems = cross_check(&mod_json, &files, &shared_objects);
+    if !problems.is_empty() {
+        println!();
+        for problem in problems.iter() {
+            println!("{} {}", "warning:".bright_yellow(), problem);
+        }
+    }
+}
+
+/// Compares what the libraries link with what the mod.json declares
+fn cross_check(
+    mod_json: &ModJson,
+    files: &[(String, u64)],
+    shared_objects: &HashMap<String, SharedObjectInfo>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let in_qmod = |name: &String| files.iter().any(|(file, _)| file == name);
+
+    for name in mod_json.mod_files.iter().chain(mod_json.library_files.iter()) {
+        if !in_qmod(name) {
+            problems.push(format!("{} is declared but not in the qmod", name));
+        }
+    }
+
+    let from_dependency = |library: &str| {
+        mod_json
+            .dependencies
+            .iter()
+            .any(|dependency| is_library_of(library, &dependency.id))
+    };
+
+    let mut linked = Vec::new();
+    for (name, info) in shared_objects.iter() {
+        for needed in info.needed_libraries() {
+            linked.push(needed);
+            if !in_qmod(needed) && !from_dependency(needed) {
+                problems.push(format!(
+                    "{} needs {}, which is not in the qmod or any of its dependencies",
+                    name, needed
+                ));
+            }
+        }
+    }
+
+    for library in mod_json.library_files.iter() {
+        if !linked.contains(&library) {
+            problems.push(format!("{} is a library file, but nothing links it", library));
+        }
+    }
+    for dependency in mod_json.dependencies.iter() {
+        if !linked.iter().any(|library| is_library_of(library, &dependency.id)) {
+            problems.push(format!(
+                "dependency {} is not linked by any library, it may only be needed at runtime",
+                dependency.id
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Libraries of dependencies are named lib<id>_<version>.so, or at least start with the id
+fn is_library_of(library: &str, id: &str) -> bool {
+    library.starts_with(&format!("lib{}", id)) || library.starts_with(id)
+}
+
+fn format_size(size: u64) -> String {
+    match size {
+        0..=1023 => format!("{} B", size),
+        1024..=1048575 => format!("{:.1} KiB", size as f64 / 1024.0),
+        _ => format!("{:.1} MiB", size as f64 / 1048576.0),
+    }
+}
+
+pub fn execute_qmod_extract_operation(extract_parameters: ExtractQmodOperationArgs) {
+    let path = &extract_parameters.file;
+    let output = extract_parameters
+        .output
+        .unwrap_or_else(|| path.with_extension(""));
+
+    let file = File::open(path)
+        .unwrap_or_else(|_| panic!("Failed to open {}", path.display().bright_red()));
+    ZipArchive::new(file)
+        .unwrap_or_else(|_| panic!("{} is not a zip file", path.display().bright_red()))
+        .extract(&output)
+        .expect("Failed to extract qmod");
+
+    println!(
+        "Extracted {} to {}",
+        path.display(),
+        output.display().bright_green()
+    );
+}
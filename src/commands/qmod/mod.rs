@@ -3,7 +3,11 @@ use std::path::PathBuf;
 use clap::{Args, Subcommand};
 use semver::Version;
 
+mod archive;
+mod check;
 mod edit;
+mod inspect;
+mod verify;
 
 use crate::data::{
     mod_json::{ModJson, PreProcessingData},
@@ -64,6 +68,18 @@ pub struct BuildQmodOperationArgs {
     ///
     #[clap(long = "include_libs")]
     pub include_libs: Option<Vec<String>>,
+
+    /// Check that the built library links exactly the libraries and dependencies in the mod.json
+    #[clap(long)]
+    pub verify: bool,
+
+    /// Update the mod.json with what --verify finds
+    #[clap(long, requires = "verify")]
+    pub fix: bool,
+
+    /// The abi of the build target to verify, defaults to the first target of the package
+    #[clap(long)]
+    pub target: Option<String>,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -79,6 +95,14 @@ pub enum QmodOperation {
     ///
     /// Some properties are not editable through the qmod edit command, these properties are either editable through the package, or not at all
     Edit(edit::EditQmodJsonOperationArgs),
+    /// Pack the generated `mod.json` and the files it lists into a `.qmod`
+    Zip(archive::ZipQmodOperationArgs),
+    /// Check a `mod.json` or `.qmod` for mistakes before the mod loader rejects it
+    Check(check::CheckQmodOperationArgs),
+    /// Show what is in a `.qmod`, and what its libraries link
+    Inspect(inspect::InspectQmodOperationArgs),
+    /// Unpack a `.qmod` into a folder
+    Extract(inspect::ExtractQmodOperationArgs),
 }
 
 pub fn execute_qmod_operation(operation: Qmod) {
@@ -86,10 +110,14 @@ pub fn execute_qmod_operation(operation: Qmod) {
         QmodOperation::Create(q) => execute_qmod_create_operation(q),
         QmodOperation::Build(b) => execute_qmod_build_operation(b),
         QmodOperation::Edit(e) => edit::execute_qmod_edit_operation(e),
+        QmodOperation::Zip(z) => archive::execute_qmod_zip_operation(z),
+        QmodOperation::Check(c) => check::execute_qmod_check_operation(c),
+        QmodOperation::Inspect(i) => inspect::execute_qmod_inspect_operation(i),
+        QmodOperation::Extract(e) => inspect::execute_qmod_extract_operation(e),
     }
 }
 
-fn execute_qmod_create_operation(create_parameters: CreateQmodJsonOperationArgs) {
+pub fn execute_qmod_create_operation(create_parameters: CreateQmodJsonOperationArgs) {
     let schema_version = match create_parameters.schema_version {
         Option::Some(s) => s,
         Option::None => Version::new(1, 0, 0),
@@ -130,16 +158,12 @@ fn execute_qmod_build_operation(build_parameters: BuildQmodOperationArgs) {
 
     println!("Generating mod.json file from template...");
     let package = PackageConfig::read();
-    let shared_package = SharedPackageConfig::from_package(&package);
-
-    let mut mod_json: ModJson = shared_package.into();
+    let shared_package = SharedPackageConfig::from_package(&package, Default::default());
 
     // Parse template mod.template.json
-    let preprocess_data = PreProcessingData {
-        version: package.info.version.to_string(),
-        mod_id: package.info.id,
-        mod_name: package.info.name,
-    };
+    let preprocess_data = PreProcessingData::new(&shared_package);
+
+    let mut mod_json: ModJson = shared_package.clone().into();
 
     let mut existing_json = ModJson::read_and_preprocess(&preprocess_data);
     if let Some(is_library) = build_parameters.is_library {
@@ -192,6 +216,18 @@ fn execute_qmod_build_operation(build_parameters: BuildQmodOperationArgs) {
     // existing_json.id = mod_json.id;
     // existing_json.version = mod_json.version;
 
+    let verified = !build_parameters.verify
+        || verify::verify_mod_json(
+            &mut existing_json,
+            &shared_package,
+            &package.get_target(build_parameters.target.as_deref()),
+            build_parameters.fix,
+        );
+
     // Write mod.json
     existing_json.write(PathBuf::from(ModJson::get_result_name()));
+
+    if !verified {
+        std::process::exit(1);
+    }
 }
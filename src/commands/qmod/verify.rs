@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use owo_colors::OwoColorize;
+
+use crate::{
+    data::{
+        dependency::SharedDependency,
+        mod_json::{ModDependency, ModJson},
+        package::{BuildTarget, SharedPackageConfig},
+    },
+    utils::elf,
+};
+
+/// Compares what the built library links with the libraries and dependencies in the mod.json,
+/// and fixes the mod.json if asked to. Returns whether the mod.json is correct now
+pub fn verify_mod_json(
+    mod_json: &mut ModJson,
+    shared_package: &SharedPackageConfig,
+    target: &BuildTarget,
+    fix: bool,
+) -> bool {
+    let own_so_name = shared_package.config.get_so_name();
+    let built = shared_package
+        .config
+        .get_build_dir(target)
+        .join(&own_so_name);
+    assert!(
+        built.exists(),
+        "{} does not exist, build the mod before verifying it",
+        built.display().bright_red()
+    );
+
+    let libs_dir = shared_package.config.get_libs_dir(target);
+    let restored: Vec<&SharedDependency> = shared_package
+        .restored_dependencies
+        .iter()
+        .filter(|dep| !dep.dev.unwrap_or(false))
+        .collect();
+
+    // follow what the libraries we bundle link, downloaded qmods bring their own
+    let mut linked: Vec<&SharedDependency> = Vec::new();
+    let mut unknown: Vec<String> = Vec::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: Vec<String> = elf::read_shared_object_file(&built)
+        .needed_libraries()
+        .cloned()
+        .collect();
+    while let Some(library) = queue.pop() {
+        if !visited.insert(library.clone()) {
+            continue;
+        }
+
+        match restored.iter().find(|dep| dep.get_so_name() == library).copied() {
+            // the modloader is always on the headset, it never goes in a qmod
+            Some(dep) if dep.dependency.id == "modloader" => {}
+            Some(dep) => {
+                linked.push(dep);
+                let path = libs_dir.join(&library);
+                if dep.dependency.additional_data.mod_link.is_none() && path.exists() {
+                    queue.extend(elf::read_shared_object_file(&path).needed_libraries().cloned());
+                }
+            }
+            None if mod_json.library_files.contains(&library) => {}
+            None => unknown.push(library),
+        }
+    }
+
+    // links to libraries that are not restored can not be fixed here
+    for library in unknown.iter() {
+        println!(
+            "{} {} links {}, which is not a restored dependency",
+            "error:".bright_red(),
+            own_so_name,
+            library
+        );
+    }
+
+    let mut problems = Vec::new();
+    let mut missing_libraries = Vec::new();
+    let mut missing_dependencies = Vec::new();
+    for dep in linked.iter() {
+        if dep.dependency.additional_data.mod_link.is_some() {
+            if !mod_json.dependencies.iter().any(|d| d.id == dep.dependency.id) {
+                problems.push(format!(
+                    "{} is linked but missing from dependencies",
+                    dep.dependency.id
+                ));
+                missing_dependencies.push(ModDependency::from((*dep).clone()));
+            }
+        } else if !mod_json.library_files.contains(&dep.get_so_name()) {
+            problems.push(format!(
+                "{} is linked but missing from libraryFiles",
+                dep.get_so_name()
+            ));
+            missing_libraries.push(dep.get_so_name());
+        }
+    }
+
+    // only entries that belong to a restored dependency can be judged, anything else was added by hand
+    let is_linked = |so_name: &String| linked.iter().any(|dep| &dep.get_so_name() == so_name);
+    let superfluous_libraries: Vec<String> = mod_json
+        .library_files
+        .iter()
+        .filter(|library| **library != own_so_name)
+        .filter(|library| restored.iter().any(|dep| &dep.get_so_name() == *library))
+        .filter(|library| !is_linked(library))
+        .cloned()
+        .collect();
+    let superfluous_dependencies: Vec<String> = mod_json
+        .dependencies
+        .iter()
+        .filter(|d| restored.iter().any(|dep| dep.dependency.id == d.id))
+        .filter(|d| !linked.iter().any(|dep| dep.dependency.id == d.id))
+        .map(|d| d.id.clone())
+        .collect();
+    for library in superfluous_libraries.iter() {
+        problems.push(format!("{} is in libraryFiles but not linked", library));
+    }
+    for id in superfluous_dependencies.iter() {
+        problems.push(format!("{} is in dependencies but not linked", id));
+    }
+
+    if problems.is_empty() {
+        if unknown.is_empty() {
+            println!("{} links exactly what mod.json lists", own_so_name.bright_green());
+        }
+        return unknown.is_empty();
+    }
+
+    for problem in problems.iter() {
+        if fix {
+            println!("{} {}", "fixed:".bright_green(), problem);
+        } else {
+            println!("{} {}", "error:".bright_red(), problem);
+        }
+    }
+    if !fix {
+        println!("Run with {} to update mod.json", "--fix".bright_yellow());
+        return false;
+    }
+
+    mod_json
+        .library_files
+        .retain(|library| !superfluous_libraries.contains(library));
+    mod_json.library_files.append(&mut missing_libraries);
+    mod_json
+        .dependencies
+        .retain(|d| !superfluous_dependencies.contains(&d.id));
+    mod_json.dependencies.append(&mut missing_dependencies);
+
+    unknown.is_empty()
+}
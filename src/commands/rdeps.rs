@@ -0,0 +1,128 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use semver::{Version, VersionReq};
+
+use crate::data::{metadata_cache::MetadataCache, qpackages};
+
+#[derive(Args, Debug, Clone)]
+pub struct RdepsOperation {
+    /// Id of the package to find the dependents of
+    pub id: String,
+
+    /// Only count dependents that accept a version matching this range
+    #[clap(long)]
+    pub version: Option<VersionReq>,
+
+    /// Also find the dependents of the dependents, and so on
+    #[clap(long)]
+    pub transitive: bool,
+}
+
+pub fn execute_rdeps_operation(operation: RdepsOperation) {
+    let mut cache = MetadataCache::read();
+    let ids = qpackages::get_packages();
+
+    let versions: Vec<Version> = qpackages::get_versions(&operation.id)
+        .unwrap_or_else(|| panic!("Could not find package {}", operation.id.bright_red()))
+        .into_iter()
+        .map(|v| v.version)
+        .filter(|v| operation.version.as_ref().map_or(true, |req| req.matches(v)))
+        .collect();
+    if versions.is_empty() {
+        panic!(
+            "No published version of {} matches {}",
+            operation.id.bright_red(),
+            operation
+                .version
+                .map(|req| req.to_string())
+                .unwrap_or_else(|| "*".to_string())
+                .bright_blue()
+        );
+    }
+
+    let index = reverse_index(&mut cache, &ids);
+
+    // breadth first, so every dependent is listed with the fewest steps it takes to reach the package
+    let mut visited = HashSet::from([operation.id.clone()]);
+    let mut queue = VecDeque::from([(operation.id.clone(), versions, 0)]);
+    let mut printed_depth = 0;
+    while let Some((id, versions, depth)) = queue.pop_front() {
+        for (dependent, dependent_versions) in find_dependents(&index, &id, &versions) {
+            // a package that depends on several of the dependents is only listed once
+            if !visited.insert(dependent.clone()) {
+                continue;
+            }
+
+            if printed_depth != depth + 1 {
+                printed_depth = depth + 1;
+                if operation.transitive {
+                    println!("Depth {}:", printed_depth.bright_yellow());
+                }
+            }
+            println!(
+                "{} {} (depends on {})",
+                dependent.bright_red(),
+                dependent_versions
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .bright_green(),
+                id
+            );
+
+            if operation.transitive {
+                queue.push_back((dependent, dependent_versions, depth + 1));
+            }
+        }
+    }
+
+    // dependencies of published versions never change, so everything looked up is worth keeping
+    cache.write();
+
+    if printed_depth == 0 {
+        println!("Nothing depends on {}", operation.id.bright_red());
+    }
+}
+
+/// The published versions that depend on a package by the id of that package, with the range they accept
+type ReverseIndex = HashMap<String, Vec<(String, Version, VersionReq)>>;
+
+/// Looks up the dependencies of every published version once, instead of for every package that is searched for
+fn reverse_index(cache: &mut MetadataCache, ids: &[String]) -> ReverseIndex {
+    let mut index = ReverseIndex::new();
+    for dependent in ids.iter() {
+        for version in qpackages::get_versions(dependent).into_iter().flatten() {
+            for dep in cache
+                .get_dependencies(dependent, &version.version)
+                .into_iter()
+                .flatten()
+            {
+                index.entry(dep.id).or_default().push((
+                    dependent.clone(),
+                    version.version.clone(),
+                    dep.version_range,
+                ));
+            }
+        }
+    }
+    index
+}
+
+/// The packages with versions that depend on any of the given versions of a package
+fn find_dependents(index: &ReverseIndex, id: &str, versions: &[Version]) -> Vec<(String, Vec<Version>)> {
+    let mut result: Vec<(String, Vec<Version>)> = Vec::new();
+    for (dependent, version, range) in index.get(id).into_iter().flatten() {
+        if dependent == id || !versions.iter().any(|v| range.matches(v)) {
+            continue;
+        }
+
+        match result.iter_mut().find(|(existing, _)| existing == dependent) {
+            Some((_, dependent_versions)) => dependent_versions.push(version.clone()),
+            None => result.push((dependent.clone(), vec![version.clone()])),
+        }
+    }
+    result
+}
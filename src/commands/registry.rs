@@ -0,0 +1,139 @@
+use std::io::Write;
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::data::{config::get_publish_keyring, qpackages};
+
+#[derive(Args, Debug, Clone)]
+pub struct Registry {
+    #[clap(subcommand)]
+    pub op: RegistryOperation,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum RegistryOperation {
+    /// Check a publish key against the registry and store it
+    Login(LoginArgs),
+    /// Print who the stored publish key belongs to
+    Whoami,
+    /// Manage who may publish a package
+    Owners(Owners),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct LoginArgs {
+    /// The publish key, asked for if not given
+    pub key: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Owners {
+    #[clap(subcommand)]
+    pub op: OwnersOperation,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum OwnersOperation {
+    /// Allow a user to publish the package
+    Add {
+        /// Id of the package
+        id: String,
+        /// The user to add
+        user: String,
+    },
+    /// Stop a user from publishing the package
+    Remove {
+        /// Id of the package
+        id: String,
+        /// The user to remove
+        user: String,
+    },
+    /// List the users that may publish the package
+    List {
+        /// Id of the package
+        id: String,
+    },
+}
+
+pub fn execute_registry_operation(operation: Registry) {
+    match operation.op {
+        RegistryOperation::Login(l) => execute_login_operation(l),
+        RegistryOperation::Whoami => execute_whoami_operation(),
+        RegistryOperation::Owners(o) => execute_owners_operation(o),
+    }
+}
+
+fn stored_key() -> String {
+    get_publish_keyring().get_password().unwrap_or_else(|_| {
+        panic!(
+            "No publish key stored, log in with {}",
+            "qpm registry login".bright_yellow()
+        )
+    })
+}
+
+fn execute_login_operation(operation: LoginArgs) {
+    let key = match operation.key {
+        Some(key) => key,
+        None => {
+            print!("Publish key for {}: ", qpackages::api_url().bright_yellow());
+            std::io::stdout().flush().expect("Failed to flush stdout");
+            let mut key = String::new();
+            std::io::stdin()
+                .read_line(&mut key)
+                .expect("Failed to read publish key");
+            key.trim().to_string()
+        }
+    };
+
+    let user = qpackages::whoami(&key).unwrap_or_else(|| {
+        panic!(
+            "{} does not accept this publish key, it was not stored",
+            qpackages::api_url().bright_yellow()
+        )
+    });
+
+    get_publish_keyring()
+        .set_password(&key)
+        .expect("Failed to set publish key");
+    println!(
+        "Logged in as {}! The key will now be used for future qpm publish calls",
+        user.username.bright_green()
+    );
+}
+
+fn execute_whoami_operation() {
+    match qpackages::whoami(&stored_key()) {
+        Some(user) => println!(
+            "Logged in to {} as {}",
+            qpackages::api_url().bright_yellow(),
+            user.username.bright_green()
+        ),
+        None => println!(
+            "The stored publish key is not accepted by {}, log in again with {}",
+            qpackages::api_url().bright_yellow(),
+            "qpm registry login".bright_yellow()
+        ),
+    }
+}
+
+fn execute_owners_operation(operation: Owners) {
+    let auth = stored_key();
+    match operation.op {
+        OwnersOperation::Add { id, user } => {
+            qpackages::add_owner(&id, &user, &auth);
+            println!("{} may now publish {}", user.bright_green(), id.bright_red());
+        }
+        OwnersOperation::Remove { id, user } => {
+            qpackages::remove_owner(&id, &user, &auth);
+            println!("{} may no longer publish {}", user.bright_green(), id.bright_red());
+        }
+        OwnersOperation::List { id } => {
+            println!("Owners of {}:", id.bright_red());
+            for owner in qpackages::get_owners(&id, &auth) {
+                println!(" - {}", owner.bright_green());
+            }
+        }
+    }
+}
@@ -1,14 +1,48 @@
 use std::io::Write;
 
-use crate::data::{
-    config::Config,
-    package::{PackageConfig, SharedPackageConfig}, repo::multi_provider::MultiDependencyProvider,
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    data::{
+        config::Config,
+        package::{PackageConfig, SharedPackageConfig},
+        repo::multi_provider::MultiDependencyProvider,
+        workspace::WorkspaceConfig,
+    },
+    resolver::ResolutionStrategy,
 };
 
-pub fn execute_restore_operation() {
-    println!("package should be restoring");
+#[derive(Args, Debug, Clone)]
+pub struct RestoreOperation {
+    /// Which versions to pick, by default versions already in qpm.shared.json are kept
+    #[clap(long, value_enum)]
+    pub resolution: Option<ResolutionStrategy>,
+
+    /// The abi of the build target to restore the libraries for, defaults to the first target of the package
+    #[clap(long)]
+    pub target: Option<String>,
+}
+
+pub fn execute_restore_operation(operation: RestoreOperation) {
+    let strategy = operation.resolution.unwrap_or_default();
+    if WorkspaceConfig::check() {
+        for (member, shared_package) in WorkspaceConfig::read().resolve(strategy) {
+            println!("Restoring {}", member.display().bright_green());
+            WorkspaceConfig::in_member(&member, || {
+                restore_package(&shared_package, operation.target.as_deref())
+            });
+        }
+        return;
+    }
+
     let package = PackageConfig::read();
-    let shared_package = SharedPackageConfig::from_package(&package);
+    let shared_package = SharedPackageConfig::from_package(&package, strategy);
+    restore_package(&shared_package, operation.target.as_deref());
+}
+
+pub fn restore_package(shared_package: &SharedPackageConfig, abi: Option<&str>) {
+    let target = shared_package.config.get_target(abi);
 
     // create used dirs
     std::fs::create_dir_all("src").expect("Failed to create directory");
@@ -28,5 +62,7 @@ pub fn execute_restore_operation() {
         // HACK: qpm rust is fast enough to where removing the folder and then remaking it is doable
         super::clear::remove_dependencies_dir();
     }
-    shared_package.restore(&MultiDependencyProvider::useful_default_new());
+    let repo = MultiDependencyProvider::for_shared_package(shared_package);
+    shared_package.warn_yanked(&repo);
+    shared_package.restore(&repo, &target);
 }
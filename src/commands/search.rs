@@ -0,0 +1,85 @@
+use clap::Args;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use owo_colors::OwoColorize;
+
+use crate::data::metadata_cache::{MetadataCache, PackageSummary};
+
+#[derive(Args, Debug, Clone)]
+pub struct SearchOperation {
+    /// What to look for in package ids and names
+    pub query: String,
+
+    /// How many results to show at most
+    #[clap(long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Print the results as json
+    #[clap(long)]
+    pub json: bool,
+}
+
+pub fn execute_search_operation(operation: SearchOperation) {
+    let mut cache = MetadataCache::read();
+    let summaries = cache.get_all();
+    cache.write();
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut results: Vec<(i64, PackageSummary)> = summaries
+        .into_iter()
+        .filter_map(|summary| {
+            let score = [&summary.id, &summary.name]
+                .into_iter()
+                .filter_map(|text| matcher.fuzzy_match(text, &operation.query))
+                .max()?;
+            // exact ids always come first
+            let score = if summary.id.eq_ignore_ascii_case(&operation.query) {
+                i64::MAX
+            } else {
+                score
+            };
+            Some((score, summary))
+        })
+        .collect();
+    results.sort_by(|(a, a_summary), (b, b_summary)| b.cmp(a).then(a_summary.id.cmp(&b_summary.id)));
+    let results: Vec<PackageSummary> = results
+        .into_iter()
+        .take(operation.limit)
+        .map(|(_, summary)| summary)
+        .collect();
+
+    if operation.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&results).expect("Serialization failed")
+        );
+        return;
+    }
+
+    if results.is_empty() {
+        println!("No packages found for {}", operation.query.bright_yellow());
+        return;
+    }
+
+    for summary in results.iter() {
+        println!(
+            "{} ({}) {}{}",
+            summary.id.bright_red(),
+            summary.name,
+            summary.version.bright_green(),
+            if summary.headers_only {
+                " [headers only]"
+            } else {
+                ""
+            }
+        );
+        if let Some(description) = &summary.description {
+            println!("    {}", description);
+        }
+        if let Some(url) = &summary.url {
+            println!("    repository: {}", url.bright_blue());
+        }
+        if let Some(mod_link) = &summary.mod_link {
+            println!("    mod: {}", mod_link.bright_blue());
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+use semver::Version;
+
+use crate::data::{config::get_publish_keyring, qpackages};
+
+#[derive(Args, Debug, Clone)]
+pub struct YankOperation {
+    /// Id of the package
+    pub id: String,
+    /// The version to yank
+    pub version: Version,
+
+    /// Why the version is yanked, shown to anyone who still has it locked
+    #[clap(long)]
+    pub reason: Option<String>,
+
+    /// Make a yanked version available again
+    #[clap(long)]
+    pub undo: bool,
+
+    /// the authorization header to use, if present
+    #[clap(long)]
+    pub publish_auth: Option<String>,
+}
+
+pub fn execute_yank_operation(operation: YankOperation) {
+    let auth = match operation.publish_auth {
+        Some(key) => key,
+        None => get_publish_keyring()
+            .get_password()
+            .expect("Unable to get stored publish key!"),
+    };
+
+    qpackages::yank_package(
+        &operation.id,
+        &operation.version,
+        !operation.undo,
+        operation.reason,
+        &auth,
+    );
+
+    println!(
+        "Package {} v{} {}",
+        operation.id.bright_red(),
+        operation.version.bright_green(),
+        if operation.undo { "unyanked" } else { "yanked" }
+    );
+}
@@ -12,7 +12,10 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ndk_path: Option<String>
+    pub ndk_path: Option<String>,
+    /// url of the package registry, qpackages.com if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
 }
 
 impl Default for Config {
@@ -23,6 +26,7 @@ impl Default for Config {
             cache: Some(dirs::data_dir().unwrap().join("QPM-Rust").join("cache")),
             timeout: Some(5000),
             ndk_path: None,
+            registry: None,
         }
     }
 }
@@ -56,6 +60,7 @@ impl Config {
                 cache: None,
                 timeout: None,
                 ndk_path: None,
+                registry: None,
             }
         }
     }
@@ -84,6 +89,9 @@ impl Config {
             if local_config.ndk_path.is_some() {
                 config.ndk_path = local_config.ndk_path;
             }
+            if local_config.registry.is_some() {
+                config.registry = local_config.registry;
+            }
         }
 
         config
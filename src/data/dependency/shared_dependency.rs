@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::Cursor,
     path::{Path, PathBuf},
@@ -15,7 +16,7 @@ use super::Dependency;
 use crate::{
     data::{
         config::Config,
-        package::{PackageConfig, SharedPackageConfig},
+        package::{BuildTarget, PackageConfig, PackageOverride, SharedPackageConfig},
     },
     utils::{git, network::get_agent},
 };
@@ -25,6 +26,15 @@ use crate::{
 pub struct SharedDependency {
     pub dependency: Dependency,
     pub version: Version,
+    /// The override of the root package this dependency was resolved with, and is restored with
+    #[serde(rename = "override", skip_serializing_if = "Option::is_none")]
+    pub package_override: Option<PackageOverride>,
+    /// Whether this dependency is only restored because of dev dependencies, these are not published
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dev: Option<bool>,
+    /// Dependencies this dependency uses privately, resolved apart from everything else as they are linked into it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub private_dependencies: Vec<SharedDependency>,
 }
 
 impl SharedDependency {
@@ -50,7 +60,68 @@ impl SharedDependency {
             ))
     }
 
-    pub fn cache(&self, shared_package: &SharedPackageConfig) {
+    /// Makes a shared dependency for a package that was resolved as part of the dependencies of root,
+    /// overrides are the ones that applied while resolving, which are not always those of root itself
+    pub fn for_package(
+        root: &PackageConfig,
+        overrides: &BTreeMap<String, PackageOverride>,
+        shared_package: SharedPackageConfig,
+    ) -> Self {
+        let version_range = if let Some(orig) = root
+            .all_dependencies()
+            .find(|el| el.id == shared_package.config.info.id)
+        {
+            orig.version_range.clone()
+        } else {
+            VersionReq::parse(&format!("^{}", shared_package.config.info.version)).unwrap()
+        };
+
+        SharedDependency {
+            dependency: Dependency {
+                id: shared_package.config.info.id.to_string(),
+                version_range,
+                additional_data: shared_package.config.info.additional_data,
+            },
+            version: shared_package.config.info.version,
+            package_override: overrides.get(&shared_package.config.info.id).cloned(),
+            dev: None,
+            private_dependencies: Vec::new(),
+        }
+    }
+
+    /// Overridden dependencies are cached separately, so a fork never ends up in the cache of the published version.
+    /// The folder is named after a hash of the override, so changing it does not reuse what an older override cached
+    fn cache_path(&self) -> PathBuf {
+        let version = match &self.package_override {
+            Some(package_override) => {
+                let json = serde_json::to_vec(package_override).expect("Serialization failed");
+                format!("{}-override-{:016x}", self.version, fnv1a(&json))
+            }
+            None => self.version.to_string(),
+        };
+
+        Config::read_combine()
+            .cache
+            .unwrap()
+            .join(&self.dependency.id)
+            .join(version)
+    }
+
+    /// Where the source of the package ends up once it is cached, sub folder already taken into account
+    pub fn cached_src_path(&self) -> PathBuf {
+        self.cache_path().join("src")
+    }
+
+    /// Where the binaries of a build target are cached, the default abi keeps the folder it had before targets existed
+    fn cache_lib_path(&self, target: &BuildTarget) -> PathBuf {
+        if target.abi == BuildTarget::DEFAULT_ABI {
+            self.cache_path().join("lib")
+        } else {
+            self.cache_path().join(format!("lib_{}", target.abi))
+        }
+    }
+
+    pub fn cache(&self, shared_package: &SharedPackageConfig, target: &BuildTarget) {
         // Check if already cached
         // if true, don't download repo / header files
         // else cache to tmp folder in package id folder @ cache path
@@ -67,20 +138,74 @@ impl SharedDependency {
             self.dependency.id.bright_red(),
             self.version.bright_green()
         );
-        let config = Config::read_combine();
-        let base_path = config
-            .cache
-            .unwrap()
-            .join(&self.dependency.id)
-            .join(self.version.to_string());
-
-        let src_path = base_path.join("src");
-        let lib_path = base_path.join("lib");
-        let tmp_path = base_path.join("tmp");
+        self.cache_src(shared_package);
 
+        let lib_path = self.cache_lib_path(target);
         let so_path = lib_path.join(shared_package.config.get_so_name());
         let debug_so_path = lib_path.join(format!("debug_{}", shared_package.config.get_so_name()));
 
+        if !lib_path.exists() {
+            std::fs::create_dir_all(&lib_path).expect("Failed to create lib path");
+            // libs didn't exist or the release object didn't exist, we need to download from the so link and debug so link of the target
+            if !so_path.exists() || File::open(&so_path).is_err() {
+                if let Some(so_link) = shared_package.config.info.additional_data.get_so_link(&target.abi) {
+                    // so_link existed, download
+                    if so_link.contains("github.com") {
+                        // github url!
+                        git::get_release(so_link, &so_path);
+                    } else {
+                        let mut response = get_agent()
+                            .get(so_link)
+                            .send()
+                            .expect("Unable to download so file");
+
+                        // other dl link, assume it's a raw lib file download
+                        let mut file =
+                            std::fs::File::create(so_path).expect("create so file failed");
+
+                        response
+                            .copy_to(&mut file)
+                            .expect("Failed to write out downloaded bytes");
+                    }
+                }
+            }
+
+            if !debug_so_path.exists() || File::open(&debug_so_path).is_err() {
+                if let Some(debug_so_link) = shared_package
+                    .config
+                    .info
+                    .additional_data
+                    .get_debug_so_link(&target.abi)
+                {
+                    // debug_so_link existed, download
+                    if debug_so_link.contains("github.com") {
+                        // github url!
+                        git::get_release(debug_so_link, &debug_so_path);
+                    } else {
+                        // other dl link, assume it's a raw lib file download
+                        let mut response = get_agent()
+                            .get(debug_so_link)
+                            .send()
+                            .expect("Unable to download debug so file");
+
+                        let mut file =
+                            std::fs::File::create(debug_so_path).expect("create so file failed");
+
+                        response
+                            .copy_to(&mut file)
+                            .expect("Failed to write out downloaded bytes");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Only caches the source of the package, for when the binaries are not needed
+    pub fn cache_src(&self, shared_package: &SharedPackageConfig) {
+        let base_path = self.cache_path();
+        let src_path = base_path.join("src");
+        let tmp_path = base_path.join("tmp");
+
         // Downloads the repo / zip file into src folder w/ subfolder taken into account
         if !src_path.exists() {
             // if the tmp path exists, but src doesn't, that's a failed cache, delete it and try again!
@@ -167,66 +292,19 @@ impl SharedDependency {
                 )
             }
         }
-
-        if !lib_path.exists() {
-            std::fs::create_dir_all(&lib_path).expect("Failed to create lib path");
-            // libs didn't exist or the release object didn't exist, we need to download from packageconfig.info.additional_data.so_link and packageconfig.info.additional_data.debug_so_link
-            if !so_path.exists() || File::open(&so_path).is_err() {
-                if let Some(so_link) = &shared_package.config.info.additional_data.so_link {
-                    // so_link existed, download
-                    if so_link.contains("github.com") {
-                        // github url!
-                        git::get_release(so_link, &so_path);
-                    } else {
-                        let mut response = get_agent()
-                            .get(so_link)
-                            .send()
-                            .expect("Unable to download so file");
-
-                        // other dl link, assume it's a raw lib file download
-                        let mut file =
-                            std::fs::File::create(so_path).expect("create so file failed");
-
-                        response
-                            .copy_to(&mut file)
-                            .expect("Failed to write out downloaded bytes");
-                    }
-                }
-            }
-
-            if !debug_so_path.exists() || File::open(&debug_so_path).is_err() {
-                if let Some(debug_so_link) =
-                    &shared_package.config.info.additional_data.debug_so_link
-                {
-                    // debug_so_link existed, download
-                    if debug_so_link.contains("github.com") {
-                        // github url!
-                        git::get_release(debug_so_link, &debug_so_path);
-                    } else {
-                        // other dl link, assume it's a raw lib file download
-                        let mut response = get_agent()
-                            .get(debug_so_link)
-                            .send()
-                            .expect("Unable to download debug so file");
-
-                        let mut file =
-                            std::fs::File::create(debug_so_path).expect("create so file failed");
-
-                        response
-                            .copy_to(&mut file)
-                            .expect("Failed to write out downloaded bytes");
-                    }
-                }
-            }
-        }
     }
 
-    pub fn restore_from_cache(&self, also_lib: bool, shared_package: &SharedPackageConfig) {
+    pub fn restore_from_cache(
+        &self,
+        also_lib: bool,
+        shared_package: &SharedPackageConfig,
+        target: &BuildTarget,
+    ) {
         // restore from cached files, give error on fail (nonexistent?)
         if Config::read_combine().symlink.unwrap_or(false) {
-            self.restore_from_cache_symlink(also_lib, shared_package);
+            self.restore_from_cache_symlink(also_lib, shared_package, target);
         } else {
-            self.restore_from_cache_copy(also_lib, shared_package);
+            self.restore_from_cache_copy(also_lib, shared_package, target);
         }
     }
 
@@ -234,19 +312,15 @@ impl SharedDependency {
         &self,
         also_lib: bool,
         shared_package: &SharedPackageConfig,
+        target: &BuildTarget,
     ) -> Vec<(PathBuf, PathBuf)> {
         // TODO: Look into improving the way it gets all the things to copy
         // low priority since this also works
-        let config = Config::read_combine();
         let package = PackageConfig::read();
 
-        let base_path = config
-            .cache
-            .unwrap()
-            .join(&self.dependency.id)
-            .join(self.version.to_string());
+        let base_path = self.cache_path();
         let src_path = base_path.join("src");
-        let libs_path = base_path.join("lib");
+        let libs_path = self.cache_lib_path(target);
         let dependencies_path = Path::new(&package.dependencies_dir);
         std::fs::create_dir_all(dependencies_path).unwrap();
         let dependencies_path = dependencies_path.canonicalize().unwrap().join("includes");
@@ -256,8 +330,7 @@ impl SharedDependency {
             let use_release = if self.dependency.additional_data.use_release.is_some() {
                 self.dependency.additional_data.use_release.unwrap()
             } else if let Some(local_dep) = package
-                .dependencies
-                .iter()
+                .all_dependencies()
                 .find(|el| el.id == self.dependency.id)
             {
                 local_dep.additional_data.use_release.unwrap_or(false)
@@ -316,6 +389,7 @@ impl SharedDependency {
                     .canonicalize()
                     .unwrap()
                     .join("libs")
+                    .join(&target.abi)
                     .join(&so_name.replace("debug_", ""));
                 // from to
                 to_copy.push((lib_so_path, local_so_path));
@@ -337,8 +411,7 @@ impl SharedDependency {
         let local_shared_package = SharedPackageConfig::read();
         if let Some(dep) = local_shared_package
             .config
-            .dependencies
-            .iter()
+            .all_dependencies()
             .find(|el| el.id == self.dependency.id)
         {
             if let Some(extra_files) = &dep.additional_data.extra_files {
@@ -353,8 +426,125 @@ impl SharedDependency {
         to_copy
     }
 
-    pub fn restore_from_cache_symlink(&self, also_lib: bool, shared_package: &SharedPackageConfig) {
-        let to_copy = self.collect_to_copy(also_lib, shared_package);
+    /// Collects what to link for a dependency that lives in a local folder instead of the cache
+    pub fn collect_to_link_local(
+        &self,
+        local_path: &Path,
+        also_lib: bool,
+        shared_package: &SharedPackageConfig,
+        target: &BuildTarget,
+    ) -> Vec<(PathBuf, PathBuf)> {
+        let package = PackageConfig::read();
+        let local_path = local_path.canonicalize().unwrap_or_else(|_| {
+            panic!(
+                "Local dependency path {} does not exist!",
+                local_path.display().bright_yellow()
+            )
+        });
+
+        let dependencies_path = Path::new(&package.dependencies_dir);
+        std::fs::create_dir_all(dependencies_path).unwrap();
+        let dependencies_path = dependencies_path.canonicalize().unwrap();
+        let includes_path = dependencies_path.join("includes").join(&self.dependency.id);
+
+        let mut to_link = Vec::new();
+        if also_lib
+            && !shared_package
+                .config
+                .info
+                .additional_data
+                .headers_only
+                .unwrap_or(false)
+        {
+            let use_release = self.dependency.additional_data.use_release.unwrap_or_else(|| {
+                package
+                    .all_dependencies()
+                    .find(|el| el.id == self.dependency.id)
+                    .and_then(|local_dep| local_dep.additional_data.use_release)
+                    .unwrap_or(false)
+            });
+
+            // the binaries of the same target, a binary of another abi would link but never load
+            let so_name = shared_package.config.get_so_name();
+            let build_dir = local_path.join(shared_package.config.get_build_dir(target));
+            let release_path = build_dir.join(&so_name);
+            let debug_path = build_dir.join("debug").join(&so_name);
+
+            let lib_so_path = if !use_release && debug_path.exists() {
+                debug_path
+            } else {
+                release_path
+            };
+
+            if !lib_so_path.exists() {
+                panic!(
+                    "Local dependency {} has not been built for {} yet, {} does not exist",
+                    self.dependency.id.bright_red(),
+                    target.abi.bright_red(),
+                    lib_so_path.display().bright_yellow()
+                );
+            }
+
+            to_link.push((
+                lib_so_path,
+                dependencies_path.join("libs").join(&target.abi).join(&so_name),
+            ));
+        }
+
+        to_link.push((
+            local_path.join(&shared_package.config.shared_dir),
+            includes_path.join(&shared_package.config.shared_dir),
+        ));
+
+        if let Some(extra_files) = &self.dependency.additional_data.extra_files {
+            for entry in extra_files.iter() {
+                to_link.push((local_path.join(entry), includes_path.join(entry)));
+            }
+        }
+
+        if let Some(dep) = package
+            .all_dependencies()
+            .find(|el| el.id == self.dependency.id)
+        {
+            if let Some(extra_files) = &dep.additional_data.extra_files {
+                for entry in extra_files.iter() {
+                    to_link.push((local_path.join(entry), includes_path.join(entry)));
+                }
+            }
+        }
+
+        to_link
+    }
+
+    /// Local dependencies are always symlinked, so changes in their folder show up without a reinstall
+    pub fn restore_from_local(
+        &self,
+        local_path: &Path,
+        also_lib: bool,
+        shared_package: &SharedPackageConfig,
+        target: &BuildTarget,
+    ) {
+        println!(
+            "Linking local dependency {} {} from {}",
+            self.dependency.id.bright_red(),
+            self.version.bright_green(),
+            local_path.display().bright_yellow()
+        );
+        let to_link = self.collect_to_link_local(local_path, also_lib, shared_package, target);
+        Self::symlink_paths(&to_link);
+    }
+
+    pub fn restore_from_cache_symlink(
+        &self,
+        also_lib: bool,
+        shared_package: &SharedPackageConfig,
+        target: &BuildTarget,
+    ) {
+        let to_copy = self.collect_to_copy(also_lib, shared_package, target);
+        Self::symlink_paths(&to_copy);
+    }
+
+    fn symlink_paths(to_copy: &[(PathBuf, PathBuf)]) {
         // sort out issues with the symlinking, stuff is being symlinked weirdly
         for (from, to) in to_copy.iter() {
             #[cfg(debug_assertions)]
@@ -391,9 +581,14 @@ impl SharedDependency {
         }
     }
 
-    pub fn restore_from_cache_copy(&self, also_lib: bool, shared_package: &SharedPackageConfig) {
+    pub fn restore_from_cache_copy(
+        &self,
+        also_lib: bool,
+        shared_package: &SharedPackageConfig,
+        target: &BuildTarget,
+    ) {
         // get the files to copy
-        let to_copy = self.collect_to_copy(also_lib, shared_package);
+        let to_copy = self.collect_to_copy(also_lib, shared_package, target);
         for (from_str, to_str) in to_copy.iter() {
             let from = Path::new(&from_str);
             let to = Path::new(&to_str);
@@ -430,24 +625,14 @@ impl SharedDependency {
 
 impl From<SharedPackageConfig> for SharedDependency {
     fn from(shared_package: SharedPackageConfig) -> Self {
-        let package_config = PackageConfig::read();
-        let version_range = if let Some(orig) = package_config
-            .dependencies
-            .iter()
-            .find(|el| el.id == shared_package.config.info.id)
-        {
-            orig.version_range.clone()
-        } else {
-            VersionReq::parse(&format!("^{}", shared_package.config.info.version)).unwrap()
-        };
-
-        SharedDependency {
-            dependency: Dependency {
-                id: shared_package.config.info.id.to_string(),
-                version_range,
-                additional_data: shared_package.config.info.additional_data,
-            },
-            version: shared_package.config.info.version,
-        }
+        let root = PackageConfig::read();
+        Self::for_package(&root, &root.overrides, shared_package)
     }
 }
+
+/// 64 bit FNV-1a, unlike the std hashers it gives the same hash with every rust version so cache folders stay put
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
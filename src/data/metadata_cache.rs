@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{config::Config, dependency::Dependency, package::SharedPackageConfig, qpackages};
+
+/// How long a cached summary is used before it is fetched again, in seconds
+static MAX_AGE: u64 = 60 * 60;
+
+/// What the registry knows about the newest version of a package
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageSummary {
+    pub id: String,
+    pub name: String,
+    pub version: Version,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub headers_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mod_link: Option<String>,
+}
+
+impl From<&SharedPackageConfig> for PackageSummary {
+    fn from(shared_package: &SharedPackageConfig) -> Self {
+        let info = &shared_package.config.info;
+        PackageSummary {
+            id: info.id.clone(),
+            name: info.name.clone(),
+            version: info.version.clone(),
+            description: info.description.clone(),
+            url: info.url.clone(),
+            headers_only: info.additional_data.headers_only.unwrap_or(false),
+            mod_link: info.additional_data.mod_link.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CachedSummary {
+    /// unix time the summary was fetched at
+    fetched: u64,
+    summary: PackageSummary,
+}
+
+/// Package summaries from the registry, kept on disk so searching doesn't fetch every package every time
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataCache {
+    /// registry the summaries came from
+    registry: String,
+    packages: HashMap<String, CachedSummary>,
+    /// dependencies of published versions by `id@version`, these never change so they are kept forever
+    #[serde(default)]
+    dependencies: HashMap<String, Vec<Dependency>>,
+}
+
+impl MetadataCache {
+    fn path() -> PathBuf {
+        Config::read_combine().cache.unwrap().join("metadata.json")
+    }
+
+    pub fn read() -> MetadataCache {
+        let registry = qpackages::api_url().to_string();
+        let cache = std::fs::File::open(Self::path())
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, MetadataCache>(file).ok())
+            .filter(|cache| cache.registry == registry);
+
+        cache.unwrap_or(MetadataCache {
+            registry,
+            packages: HashMap::new(),
+            dependencies: HashMap::new(),
+        })
+    }
+
+    pub fn write(&self) {
+        let path = Self::path();
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to create cache folder");
+        let file = std::fs::File::create(path).expect("create failed");
+        serde_json::to_writer_pretty(file, &self).expect("Serialization failed");
+    }
+
+    /// The dependencies a version of a package was published with
+    pub fn get_dependencies(&mut self, id: &str, version: &Version) -> Option<Vec<Dependency>> {
+        let key = format!("{id}@{version}");
+        if let Some(dependencies) = self.dependencies.get(&key) {
+            return Some(dependencies.clone());
+        }
+
+        let dependencies = qpackages::get_shared_package(id, version)?.config.dependencies;
+        self.dependencies.insert(key, dependencies.clone());
+        Some(dependencies)
+    }
+
+    /// The summaries of every package on the registry, fetching the ones that are missing or too old.
+    /// An old summary is only fetched again when a newer version was published since
+    pub fn get_all(&mut self) -> Vec<PackageSummary> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+
+        let ids = qpackages::get_packages();
+        self.packages.retain(|id, _| ids.contains(id));
+
+        ids.iter()
+            .filter_map(|id| {
+                if let Some(cached) = self.packages.get(id) {
+                    if now.saturating_sub(cached.fetched) < MAX_AGE {
+                        return Some(cached.summary.clone());
+                    }
+                }
+
+                let latest = qpackages::get_versions(id)?
+                    .into_iter()
+                    .filter(|v| !v.yanked.unwrap_or(false))
+                    .map(|v| v.version)
+                    .max()?;
+
+                // the version list is one small request, the package itself only when it changed
+                if let Some(cached) = self.packages.get_mut(id) {
+                    if cached.summary.version == latest {
+                        cached.fetched = now;
+                        return Some(cached.summary.clone());
+                    }
+                }
+
+                let summary = PackageSummary::from(&qpackages::get_shared_package(id, &latest)?);
+                self.packages.insert(
+                    id.clone(),
+                    CachedSummary {
+                        fetched: now,
+                        summary: summary.clone(),
+                    },
+                );
+                Some(summary)
+            })
+            .collect()
+    }
+}
@@ -4,4 +4,6 @@ pub mod mod_json;
 pub mod package;
 pub mod qpackages;
 pub mod file_repository;
-pub mod repo;
\ No newline at end of file
+pub mod metadata_cache;
+pub mod repo;
+pub mod workspace;
\ No newline at end of file
@@ -1,14 +1,17 @@
 use std::{
     io::{BufReader, Read},
-    path::PathBuf, collections::HashSet,
+    path::PathBuf, collections::{HashMap, HashSet},
 };
 
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 
-use crate::data::{
-    dependency::{Dependency, SharedDependency},
-    package::SharedPackageConfig,
+use crate::{
+    data::{
+        dependency::{Dependency, SharedDependency},
+        package::SharedPackageConfig,
+    },
+    utils::template,
 };
 
 // TODO: Idea for later, maybe some kind of config that stores defaults for the different fields, like description and author?
@@ -54,6 +57,7 @@ pub struct ModJson {
     /// list of files that will be copied on the quest
     pub file_copies: Vec<FileCopy>,
     /// list of copy extensions registered for this specific mod
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub copy_extensions: Vec<CopyExtension>,
 }
 
@@ -113,10 +117,97 @@ pub struct CopyExtension {
     pub destination: String,
 }
 
+/// Everything the variables in a mod.template.json can refer to
 pub struct PreProcessingData {
     pub version: String,
     pub mod_id: String,
     pub mod_name: String,
+    /// The package info as json, so any of its fields can be used by name
+    pub info: serde_json::Value,
+    /// The restored version of every dependency
+    pub dependency_versions: HashMap<String, String>,
+}
+
+impl PreProcessingData {
+    pub fn new(shared_package: &SharedPackageConfig) -> Self {
+        let info = &shared_package.config.info;
+        Self {
+            version: info.version.to_string(),
+            mod_id: info.id.clone(),
+            mod_name: info.name.clone(),
+            info: serde_json::to_value(info).expect("Serialization failed"),
+            dependency_versions: shared_package
+                .restored_dependencies
+                .iter()
+                .map(|dep| (dep.dependency.id.clone(), dep.version.to_string()))
+                .collect(),
+        }
+    }
+
+    /// The value of a template variable, or None if it is not known
+    ///
+    /// - `version`, `mod_id` and `mod_name`
+    /// - `env:NAME` for environment variables
+    /// - `git:tag` and `git:commit` for the current checkout
+    /// - `dep:id` for the restored version of a dependency
+    /// - any field of the package info or its additional data, like `url` or `additionalData.soLink`
+    pub fn lookup(&self, name: &str) -> Option<String> {
+        match name {
+            "version" => return Some(self.version.clone()),
+            "mod_id" => return Some(self.mod_id.clone()),
+            "mod_name" => return Some(self.mod_name.clone()),
+            "git:tag" => return git_output(&["describe", "--tags", "--abbrev=0"]),
+            "git:commit" => return git_output(&["rev-parse", "HEAD"]),
+            _ => {}
+        }
+
+        if let Some(variable) = name.strip_prefix("env:") {
+            return std::env::var(variable).ok();
+        }
+        if let Some(id) = name.strip_prefix("dep:") {
+            return self.dependency_versions.get(id).cloned();
+        }
+
+        let path: Vec<String> = name
+            .strip_prefix("info.")
+            .unwrap_or(name)
+            .split('.')
+            .map(to_camel_case)
+            .collect();
+        let value = json_path(&self.info, &path)
+            .or_else(|| json_path(&self.info["additionalData"], &path))?;
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+fn json_path<'a>(value: &'a serde_json::Value, path: &[String]) -> Option<&'a serde_json::Value> {
+    path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+/// Field names are camelCase in json, but snake_case is accepted too
+fn to_camel_case(name: &str) -> String {
+    let mut parts = name.split('_');
+    let mut result = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+    result
+}
+
+fn git_output(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
 }
 
 impl ModJson {
@@ -150,9 +241,13 @@ impl ModJson {
     }
 
     fn preprocess(s: String, preprocess_data: &PreProcessingData) -> String {
-        s.replace("${version}", preprocess_data.version.as_str())
-            .replace("${mod_id}", preprocess_data.mod_id.as_str())
-            .replace("${mod_name}", preprocess_data.mod_name.as_str())
+        template::render(&s, |name| {
+            // values end up inside json strings
+            preprocess_data.lookup(name).map(|value| {
+                let escaped = serde_json::to_string(&value).expect("Serialization failed");
+                escaped[1..escaped.len() - 1].to_string()
+            })
+        })
     }
 
     pub fn read(path: PathBuf) -> ModJson {
@@ -174,6 +269,11 @@ impl From<SharedPackageConfig> for ModJson {
 
         // Only bundle mods that are not specifically excluded in qpm.json or if they're not header-only
         shared_package.restored_dependencies.retain(|dep| {
+            // dev dependencies never go into the qmod
+            if dep.dev.unwrap_or(false) {
+                return false;
+            }
+
             let local_dep_opt = local_deps.iter().find(|local_dep| local_dep.id == dep.dependency.id);
 
             if let Some(local_dep) = local_dep_opt {
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// A binary the package is built as, every target gets its own library
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTarget {
+    /// The android abi, ex. 'arm64-v8a'
+    pub abi: String,
+    /// The android platform level, ex. 24
+    pub platform: u32,
+    /// The c++ standard library, ex. 'c++_static'
+    pub stl: String,
+}
+
+impl BuildTarget {
+    /// The abi every package was built for before targets existed, soLink and debugSoLink are for this abi
+    pub const DEFAULT_ABI: &'static str = "arm64-v8a";
+}
+
+impl Default for BuildTarget {
+    fn default() -> Self {
+        Self {
+            abi: Self::DEFAULT_ABI.to_string(),
+            platform: 24,
+            stl: "c++_static".to_string(),
+        }
+    }
+}
+
+/// The download links of the binaries of one build target
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetLinks {
+    /// the link to the so file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub so_link: Option<String>,
+
+    /// the link to the debug .so file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_so_link: Option<String>,
+}
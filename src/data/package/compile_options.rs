@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 /// - cppFeatures - OPTIONAL (System.String[]): Additional C++ features to add.
 /// - cppFlags - OPTIONAL (System.String[]): Additional C++ flags to add.
 /// - cFlags - OPTIONAL (System.String[]): Additional C flags to add.
+/// - defines - OPTIONAL (System.String[]): Additional preprocessor definitions to add.
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CompileOptions {
@@ -29,4 +30,8 @@ pub struct CompileOptions {
     /// Additional C flags to add.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub c_flags: Option<Vec<String>>,
+
+    /// Additional preprocessor definitions to add.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defines: Option<Vec<String>>,
 }
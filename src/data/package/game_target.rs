@@ -0,0 +1,12 @@
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+/// The game a package is made for
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GameTarget {
+    /// id of the game, the same as the packageId in a mod.json
+    pub id: String,
+    /// versions of the game this package works with
+    pub version_range: VersionReq,
+}
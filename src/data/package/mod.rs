@@ -1,6 +1,19 @@
+mod build_target;
+pub type BuildTarget = build_target::BuildTarget;
+pub type TargetLinks = build_target::TargetLinks;
+
 mod compile_options;
 pub type CompileOptions = compile_options::CompileOptions;
 
+mod game_target;
+pub type GameTarget = game_target::GameTarget;
+
+mod package_feature;
+pub type PackageFeature = package_feature::PackageFeature;
+
+mod package_override;
+pub type PackageOverride = package_override::PackageOverride;
+
 mod package_config;
 pub type PackageConfig = package_config::PackageConfig;
 pub type PackageInfo = package_config::PackageInfo;
@@ -1,10 +1,16 @@
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use owo_colors::OwoColorize;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use super::{CompileOptions, SharedPackageConfig};
+use super::{
+    BuildTarget, CompileOptions, GameTarget, PackageFeature, PackageOverride, SharedPackageConfig,
+    TargetLinks,
+};
 use crate::data::dependency::{AdditionalDependencyData, Dependency};
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -13,7 +19,19 @@ pub struct PackageConfig {
     pub dependencies_dir: PathBuf,
     pub info: PackageInfo,
     pub dependencies: Vec<Dependency>,
+    /// Dependencies only used while developing this package, they are not published and never end up in the mod.json
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dev_dependencies: Vec<Dependency>,
     pub additional_data: AdditionalDependencyData,
+    /// Named features that consumers can enable, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, PackageFeature>,
+    /// Overrides for packages anywhere in the dependency graph, by id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, PackageOverride>,
+    /// The targets this package is built for, a single arm64-v8a target when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<BuildTarget>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq)]
@@ -23,6 +41,12 @@ pub struct PackageInfo {
     pub id: String,
     pub version: Version,
     pub url: Option<String>,
+    /// What the package is for, shown when searching the registry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The game and game versions this package is made for, dependencies have to target the same game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game: Option<GameTarget>,
     pub additional_data: AdditionalPackageData,
 }
 
@@ -85,6 +109,16 @@ pub struct AdditionalPackageData {
     )]
     pub is_private: Option<bool>,
 
+    /// Optional dependencies are only used when one of the features of this package enables them
+    /// Technically just a dependency field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optional: Option<bool>,
+
+    /// The features of this dependency to enable
+    /// Technically just a dependency field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<Vec<String>>,
+
     /// Additional Compile options to be used with this package
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compile_options: Option<CompileOptions>,
@@ -92,6 +126,30 @@ pub struct AdditionalPackageData {
     /// Sub folder to use from the downloaded repo / zip, so one repo can contain multiple packages
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sub_folder: Option<String>,
+
+    /// Links to the binaries of build targets by abi, soLink and debugSoLink are used for arm64-v8a if it is not in here
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_links: Option<BTreeMap<String, TargetLinks>>,
+}
+
+impl AdditionalPackageData {
+    /// The link to the so file of a build target
+    pub fn get_so_link(&self, abi: &str) -> Option<&String> {
+        match self.target_links.as_ref().and_then(|links| links.get(abi)) {
+            Some(links) => links.so_link.as_ref(),
+            None if abi == BuildTarget::DEFAULT_ABI => self.so_link.as_ref(),
+            None => None,
+        }
+    }
+
+    /// The link to the debug .so file of a build target
+    pub fn get_debug_so_link(&self, abi: &str) -> Option<&String> {
+        match self.target_links.as_ref().and_then(|links| links.get(abi)) {
+            Some(links) => links.debug_so_link.as_ref(),
+            None if abi == BuildTarget::DEFAULT_ABI => self.debug_so_link.as_ref(),
+            None => None,
+        }
+    }
 }
 
 impl PackageConfig {
@@ -135,6 +193,32 @@ impl PackageConfig {
         }
     }
 
+    pub fn add_dev_dependency(&mut self, dependency: Dependency) {
+        if self.all_dependencies().any(|dep| dep.id == dependency.id) {
+            println!(
+                "Not adding dev dependency {} because it already existed",
+                &dependency.id
+            );
+        } else {
+            self.dev_dependencies.push(dependency);
+        }
+    }
+
+    /// Whether a dependency is used when these features of this package are enabled
+    pub fn is_dependency_enabled(&self, dependency: &Dependency, features: &[String]) -> bool {
+        !dependency.additional_data.optional.unwrap_or(false)
+            || features.iter().any(|feature| {
+                self.features
+                    .get(feature)
+                    .map_or(false, |f| f.dependencies.contains(&dependency.id))
+            })
+    }
+
+    /// Both the regular and the dev dependencies, which is what gets restored for this package
+    pub fn all_dependencies(&self) -> impl Iterator<Item = &Dependency> {
+        self.dependencies.iter().chain(self.dev_dependencies.iter())
+    }
+
     pub fn get_dependency(&mut self, id: &str) -> Option<&mut Dependency> {
         for (idx, dependency) in self.dependencies.iter().enumerate() {
             if dependency.id.eq(id) {
@@ -154,11 +238,22 @@ impl PackageConfig {
             }
         }
 
+        for (idx, dependency) in self.dev_dependencies.iter().enumerate() {
+            if dependency.id.eq(id) {
+                println!("removed dev dependency {}", id);
+                self.dev_dependencies.remove(idx);
+                return;
+            }
+        }
+
         println!("Not removing dependency {} because it did not exist", id);
     }
 
-    pub fn resolve(&self) -> impl Iterator<Item = SharedPackageConfig> + '_ {
-        crate::resolver::resolve(self)
+    pub fn resolve(
+        &self,
+        strategy: crate::resolver::ResolutionStrategy,
+    ) -> impl Iterator<Item = SharedPackageConfig> + '_ {
+        crate::resolver::resolve(self, strategy)
     }
 
     pub fn get_module_id(&self) -> String {
@@ -170,6 +265,61 @@ impl PackageConfig {
         }
     }
 
+    /// The targets this package is built for
+    pub fn get_targets(&self) -> Vec<BuildTarget> {
+        if self.targets.is_empty() {
+            vec![BuildTarget::default()]
+        } else {
+            self.targets.clone()
+        }
+    }
+
+    /// The target with this abi, or the first target when no abi is given
+    pub fn get_target(&self, abi: Option<&str>) -> BuildTarget {
+        let targets = self.get_targets();
+        match abi {
+            None => targets[0].clone(),
+            Some(abi) => targets
+                .iter()
+                .find(|target| target.abi == abi)
+                .cloned()
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Package {} has no target {}, its targets are {}",
+                        self.info.id.bright_red(),
+                        abi.bright_red(),
+                        targets
+                            .iter()
+                            .map(|target| target.abi.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                            .bright_yellow()
+                    )
+                }),
+        }
+    }
+
+    /// Where the binaries of a target are built to, debug binaries are in its debug folder
+    ///
+    /// Packages without targets keep building to the build folder itself
+    pub fn get_build_dir(&self, target: &BuildTarget) -> PathBuf {
+        if self.targets.is_empty() {
+            PathBuf::from("build")
+        } else {
+            Path::new("build").join(&target.abi)
+        }
+    }
+
+    /// Where the libraries of dependencies are restored to for a target
+    pub fn get_libs_dir(&self, target: &BuildTarget) -> PathBuf {
+        self.dependencies_dir.join("libs").join(&target.abi)
+    }
+
+    /// The file name of the qmod of this package in the build folder
+    pub fn get_qmod_name(&self) -> String {
+        format!("{}_{}.qmod", self.info.id, self.info.version)
+    }
+
     pub fn get_so_name(&self) -> String {
         self.info
             .additional_data
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use super::CompileOptions;
+
+/// A named set of optional functionality that consumers can enable on their dependency
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageFeature {
+    /// Ids of the optional dependencies this feature enables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+
+    /// Compile options added to consumers that enable this feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compile_options: Option<CompileOptions>,
+}
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use super::{SharedPackageConfig, TargetLinks};
+
+/// Changes how a package is resolved and restored, everywhere it appears in the dependency graph
+///
+/// Packages with overrides can not be published
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageOverride {
+    /// Use exactly this version, no matter which version ranges ask for the package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+
+    /// Download the source from this url instead, for example a fork
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Branch name to use with the overridden url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_name: Option<String>,
+
+    /// the link to the so file to use instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub so_link: Option<String>,
+
+    /// the link to the debug .so file to use instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_so_link: Option<String>,
+
+    /// The links to the binaries of every build target to use instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_links: Option<BTreeMap<String, TargetLinks>>,
+
+    /// Use the package in this folder, relative to the root package, instead of a published one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+}
+
+impl PackageOverride {
+    /// Replaces the download locations of the package, version and local path are handled by the resolver
+    pub fn apply(&self, shared_package: &mut SharedPackageConfig) {
+        let info = &mut shared_package.config.info;
+        if let Some(url) = &self.url {
+            info.url = Some(url.clone());
+        }
+        if let Some(branch_name) = &self.branch_name {
+            info.additional_data.branch_name = Some(branch_name.clone());
+        }
+        if let Some(so_link) = &self.so_link {
+            info.additional_data.so_link = Some(so_link.clone());
+        }
+        if let Some(debug_so_link) = &self.debug_so_link {
+            info.additional_data.debug_so_link = Some(debug_so_link.clone());
+        }
+        // the binaries of the original would not match the overridden ones, so none of its target links are kept
+        if self.so_link.is_some() || self.debug_so_link.is_some() || self.target_links.is_some() {
+            info.additional_data.target_links = self.target_links.clone();
+        }
+    }
+}
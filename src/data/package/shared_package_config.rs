@@ -1,13 +1,19 @@
 use std::{
+    collections::{HashMap, HashSet},
     io::{Read, Write},
+    path::{Path, PathBuf},
     vec,
 };
 
+use owo_colors::OwoColorize;
 use serde::{Deserialize, Serialize};
 
 use std::fmt::Write as _;
 
-use crate::data::{qpackages, repo::DependencyRepository};
+use crate::{
+    data::{qpackages, repo::DependencyRepository},
+    resolver,
+};
 /// Fern: Adds line ending after each element
 /// thanks raft
 macro_rules! concatln {
@@ -16,7 +22,7 @@ macro_rules! concatln {
     }
 }
 
-use super::PackageConfig;
+use super::{BuildTarget, CompileOptions, PackageConfig};
 use crate::data::dependency::SharedDependency;
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +34,10 @@ pub struct SharedPackageConfig {
 }
 
 impl SharedPackageConfig {
+    pub fn check() -> bool {
+        std::path::Path::new("qpm.shared.json").exists()
+    }
+
     pub fn read() -> SharedPackageConfig {
         let mut file =
             std::fs::File::open("qpm.shared.json").expect("Opening qpm.shared.json failed");
@@ -39,6 +49,11 @@ impl SharedPackageConfig {
             .expect("Deserializing package failed")
     }
 
+    pub fn read_path(filepath: PathBuf) -> SharedPackageConfig {
+        let file = std::fs::File::open(filepath).expect("Opening qpm.shared.json failed");
+        serde_json::from_reader(file).expect("Deserializing package failed")
+    }
+
     pub fn write(&self) {
         let qpm_package = serde_json::to_string_pretty(&self).expect("Serialization failed");
 
@@ -53,38 +68,136 @@ impl SharedPackageConfig {
         qpackages::publish_package(self, auth);
     }
 
-    pub fn from_package(package: &PackageConfig) -> SharedPackageConfig {
-        let shared_iter = package.resolve();
+    pub fn from_package(
+        package: &PackageConfig,
+        strategy: resolver::ResolutionStrategy,
+    ) -> SharedPackageConfig {
+        let resolved: HashMap<String, SharedPackageConfig> = package
+            .resolve(strategy)
+            .map(|cfg| (cfg.config.info.id.clone(), cfg))
+            .collect();
+
+        let mut locked_private = resolver::locked_private_versions(Path::new("."));
+
+        // anything that can't be reached without the dev dependencies is not needed by consumers
+        let non_dev: HashSet<&str> = resolver::reachable(package.dependencies.iter(), &resolved)
+            .into_iter()
+            .map(|cfg| cfg.config.info.id.as_str())
+            .collect();
+
+        let mut restored_dependencies: Vec<SharedDependency> = resolved
+            .values()
+            .map(|cfg| {
+                let mut shared_dep =
+                    SharedDependency::for_package(package, &package.overrides, cfg.clone());
+                if !non_dev.contains(cfg.config.info.id.as_str()) {
+                    shared_dep.dev = Some(true);
+                }
+                shared_dep.private_dependencies = resolver::resolve_private(
+                    cfg,
+                    strategy,
+                    locked_private.remove(&cfg.config.info.id).unwrap_or_default(),
+                );
+                shared_dep
+            })
+            .collect();
+        // the resolver gives no order, sorting keeps qpm.shared.json the same between restores
+        restored_dependencies.sort_by(|a, b| a.dependency.id.cmp(&b.dependency.id));
 
         SharedPackageConfig {
             config: package.clone(),
-            restored_dependencies: shared_iter
-                // this is not needed right?
-                //.collect::<Vec<SharedPackageConfig>>()
-                //.iter()
-                .map(|cfg| cfg.into())
-                .collect::<Vec<SharedDependency>>(),
+            restored_dependencies,
         }
     }
 
-    pub fn restore(&self, repo: &impl DependencyRepository) {
-        // TODO: Support restoring file repository dependencies
+    /// Warns about restored versions that were yanked after they got locked
+    pub fn warn_yanked(&self, repo: &impl DependencyRepository) {
+        for restored in self.restored_dependencies.iter() {
+            let yanked = repo
+                .get_versions(&restored.dependency.id)
+                .into_iter()
+                .flatten()
+                .find(|v| v.version == restored.version && v.yanked.unwrap_or(false));
+
+            if let Some(yanked) = yanked {
+                println!(
+                    "{} {} {} has been yanked{}",
+                    "Warning:".bright_yellow(),
+                    yanked.id.bright_red(),
+                    yanked.version.bright_green(),
+                    yanked
+                        .yanked_reason
+                        .map(|reason| format!(": {reason}"))
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    pub fn restore(&self, repo: &impl DependencyRepository, target: &BuildTarget) {
+        self.check_private_dependencies();
+
         for to_restore in self.restored_dependencies.iter() {
             // if the shared dep is contained within the direct dependencies, link against that, always copy headers!
-            let shared_package = repo.get_shared_package_from_dependency(to_restore).unwrap_or_else(|| panic!("Could not find package {}", to_restore.dependency.id));
-
-            to_restore.cache(&shared_package);
-            to_restore.restore_from_cache(
-                self.config
-                    .dependencies
-                    .iter()
-                    .any(|dep| dep.id == to_restore.dependency.id),
-                    &shared_package
-            );
+            let mut shared_package = repo.get_shared_package_from_dependency(to_restore).unwrap_or_else(|| panic!("Could not find package {}", to_restore.dependency.id));
+            // the override it was resolved with, in a workspace that may come from another member
+            if let Some(package_override) = &to_restore.package_override {
+                package_override.apply(&mut shared_package);
+            }
+            let also_lib = self
+                .config
+                .all_dependencies()
+                .any(|dep| dep.id == to_restore.dependency.id);
+
+            // local path dependencies are linked straight from their folder, they never touch the cache
+            if let Some(local_path) = &to_restore.dependency.additional_data.local_path {
+                to_restore.restore_from_local(std::path::Path::new(local_path), also_lib, &shared_package, target);
+                continue;
+            }
+
+            to_restore.cache(&shared_package, target);
+            to_restore.restore_from_cache(also_lib, &shared_package, target);
         }
 
         self.write_extern_cmake(repo);
-        self.write_define_cmake();
+        self.write_define_cmake(target);
+    }
+
+    /// Statically linked private dependencies end up inside the library of the dependency using them,
+    /// so they may not be a different version of a library that is restored into extern/libs
+    fn check_private_dependencies(&self) {
+        for restored in self.restored_dependencies.iter() {
+            let static_privates = restored.private_dependencies.iter().filter(|private| {
+                private
+                    .dependency
+                    .additional_data
+                    .static_linking
+                    .unwrap_or(false)
+            });
+
+            for private in static_privates {
+                let colliding = self.restored_dependencies.iter().find(|public| {
+                    !public.dependency.additional_data.headers_only.unwrap_or(false)
+                        && (public.dependency.id == private.dependency.id
+                            || public.get_so_name() == private.get_so_name())
+                });
+
+                if let Some(public) = colliding {
+                    if public.dependency.id != private.dependency.id
+                        || public.version != private.version
+                    {
+                        panic!(
+                            "{} statically links {} {} privately, which collides with {} {} in extern/libs",
+                            restored.dependency.id.bright_red(),
+                            private.dependency.id.bright_red(),
+                            private.version.bright_green(),
+                            public.dependency.id.bright_red(),
+                            public.version.bright_green()
+                        );
+                    }
+                }
+            }
+        }
     }
 
     pub fn write_extern_cmake(&self, repo: &impl DependencyRepository) {
@@ -104,52 +217,23 @@ impl SharedPackageConfig {
             let package_id = shared_package.config.info.id;
 
             if let Some(compile_options) =
-                shared_package.config.info.additional_data.compile_options
+                &shared_package.config.info.additional_data.compile_options
             {
                 any = true;
-                // TODO: Must ${{COMPILE_ID}} be changed to {package_id}?
-
-                if let Some(include_dirs) = compile_options.include_paths {
-                    for dir in include_dirs.iter() {
-                        writeln!(result, "target_include_directories(${{COMPILE_ID}} PRIVATE ${{EXTERN_DIR}}/includes/{}/{})", package_id, dir).unwrap();
-                    }
-                }
-
-                if let Some(system_include_dirs) = compile_options.system_includes {
-                    for dir in system_include_dirs.iter() {
-                        writeln!(result, "target_include_directories(${{COMPILE_ID}} SYSTEM PRIVATE ${{EXTERN_DIR}}/includes/{}/{})", package_id, dir).unwrap();
-                    }
-                }
-
-                let mut features: Vec<String> = vec![];
-
-                if let Some(cpp_features) = compile_options.cpp_features {
-                    features.append(&mut cpp_features.clone());
-                }
-
-                for feature in features.iter() {
-                    writeln!(result, 
-                        "target_compile_features(${{COMPILE_ID}} PRIVATE {})",
-                        feature
-                    ).unwrap();
-                }
-
-                let mut flags: Vec<String> = vec![];
-
-                if let Some(cpp_flags) = compile_options.cpp_flags {
-                    flags.append(&mut cpp_flags.clone());
-                }
-
-                if let Some(c_flags) = compile_options.c_flags {
-                    flags.append(&mut c_flags.clone());
-                }
+                Self::write_compile_options(&mut result, &package_id, compile_options);
+            }
 
-                for flag in flags.iter() {
-                    writeln!(
-                        result,
-                        "target_compile_options(${{COMPILE_ID}} PRIVATE {})",
-                        flag
-                    ).unwrap();
+            // features the dependency was restored with can add their own compile options
+            for feature_name in shared_dep.dependency.additional_data.features.iter().flatten() {
+                if let Some(compile_options) = shared_package
+                    .config
+                    .features
+                    .get(feature_name)
+                    .and_then(|feature| feature.compile_options.as_ref())
+                {
+                    any = true;
+                    writeln!(result, "# feature {} of {}", feature_name, package_id).unwrap();
+                    Self::write_compile_options(&mut result, &package_id, compile_options);
                 }
             }
 
@@ -206,8 +290,7 @@ impl SharedPackageConfig {
 
             if let Some(dep) = self
                 .config
-                .dependencies
-                .iter()
+                .all_dependencies()
                 .find(|el| el.id == shared_dep.dependency.id)
             {
                 if let Some(extra_files) = &dep.additional_data.extra_files {
@@ -269,10 +352,10 @@ impl SharedPackageConfig {
         }
 
         result.push_str(concatln!(
-            "\n# libs dir -> stores .so or .a files (or symlinked!)",
-            "target_link_directories(${COMPILE_ID} PRIVATE ${EXTERN_DIR}/libs)",
-            "RECURSE_FILES(so_list ${EXTERN_DIR}/libs/*.so)",
-            "RECURSE_FILES(a_list ${EXTERN_DIR}/libs/*.a)\n",
+            "\n# libs dir -> stores .so or .a files (or symlinked!), one folder per abi",
+            "target_link_directories(${COMPILE_ID} PRIVATE ${EXTERN_DIR}/libs/${ANDROID_ABI})",
+            "RECURSE_FILES(so_list ${EXTERN_DIR}/libs/${ANDROID_ABI}/*.so)",
+            "RECURSE_FILES(a_list ${EXTERN_DIR}/libs/${ANDROID_ABI}/*.a)\n",
             "# every .so or .a that needs to be linked, put here!",
             "# I don't believe you need to specify if a lib is static or not, poggers!",
             "target_link_libraries(${COMPILE_ID} PRIVATE\n\t${so_list}\n\t${a_list}\n)\n"
@@ -283,16 +366,73 @@ impl SharedPackageConfig {
             .expect("Failed to write out extern cmake file");
     }
 
-    pub fn write_define_cmake(&self) {
+    fn write_compile_options(result: &mut String, package_id: &str, compile_options: &CompileOptions) {
+        // TODO: Must ${{COMPILE_ID}} be changed to {package_id}?
+
+        if let Some(include_dirs) = &compile_options.include_paths {
+            for dir in include_dirs.iter() {
+                writeln!(result, "target_include_directories(${{COMPILE_ID}} PRIVATE ${{EXTERN_DIR}}/includes/{}/{})", package_id, dir).unwrap();
+            }
+        }
+
+        if let Some(system_include_dirs) = &compile_options.system_includes {
+            for dir in system_include_dirs.iter() {
+                writeln!(result, "target_include_directories(${{COMPILE_ID}} SYSTEM PRIVATE ${{EXTERN_DIR}}/includes/{}/{})", package_id, dir).unwrap();
+            }
+        }
+
+        let mut features: Vec<String> = vec![];
+
+        if let Some(cpp_features) = &compile_options.cpp_features {
+            features.append(&mut cpp_features.clone());
+        }
+
+        for feature in features.iter() {
+            writeln!(result, 
+                "target_compile_features(${{COMPILE_ID}} PRIVATE {})",
+                feature
+            ).unwrap();
+        }
+
+        let mut flags: Vec<String> = vec![];
+
+        if let Some(cpp_flags) = &compile_options.cpp_flags {
+            flags.append(&mut cpp_flags.clone());
+        }
+
+        if let Some(c_flags) = &compile_options.c_flags {
+            flags.append(&mut c_flags.clone());
+        }
+
+        for flag in flags.iter() {
+            writeln!(
+                result,
+                "target_compile_options(${{COMPILE_ID}} PRIVATE {})",
+                flag
+            ).unwrap();
+        }
+
+        if let Some(defines) = &compile_options.defines {
+            for define in defines.iter() {
+                writeln!(
+                    result,
+                    "target_compile_definitions(${{COMPILE_ID}} PRIVATE {})",
+                    define
+                ).unwrap();
+            }
+        }
+    }
+
+    pub fn write_define_cmake(&self, target: &BuildTarget) {
         let mut defines_cmake_file = std::fs::File::create("qpm_defines.cmake")
             .expect("Failed to create defines cmake file");
 
         defines_cmake_file
-            .write_all(self.make_defines_string().as_bytes())
+            .write_all(self.make_defines_string(target).as_bytes())
             .expect("Failed to write out own define make string");
     }
 
-    pub fn make_defines_string(&self) -> String {
+    pub fn make_defines_string(&self, target: &BuildTarget) -> String {
         // TODO: use additional_data.compile_options here or in the extern cmake file ? include dirs are set there at least
         let mut result: String = concatln!(
             "# YOU SHOULD NOT MANUALLY EDIT THIS FILE, QPM WILL VOID ALL CHANGES",
@@ -375,10 +515,14 @@ impl SharedPackageConfig {
                 "\tmessage(Big time error buddy, no NDK)",
             "endif()",
             "message(Using NDK ${CMAKE_ANDROID_NDK})",
-            "string(REPLACE \"\\\\\" \"/\" CMAKE_ANDROID_NDK ${CMAKE_ANDROID_NDK})",
-            "\nset(ANDROID_PLATFORM 24)",
-            "set(ANDROID_ABI arm64-v8a)",
-            "set(ANDROID_STL c++_static)",
+            "string(REPLACE \"\\\\\" \"/\" CMAKE_ANDROID_NDK ${CMAKE_ANDROID_NDK})"
+        ));
+
+        result.push_str("\n# the build target qpm restored the libraries for\n");
+        writeln!(result, "set(ANDROID_PLATFORM {})", target.platform).unwrap();
+        writeln!(result, "set(ANDROID_ABI {})", target.abi).unwrap();
+        writeln!(result, "set(ANDROID_STL {})", target.stl).unwrap();
+        result.push_str(concatln!(
             "set(ANDROID_USE_LEGACY_TOOLCHAIN_FILE OFF)",
             "\nset(CMAKE_TOOLCHAIN_FILE ${CMAKE_ANDROID_NDK}/build/cmake/android.toolchain.cmake)"
         ));
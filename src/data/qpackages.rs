@@ -5,9 +5,23 @@ use reqwest::{blocking::Response, StatusCode};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use crate::{data::{package::SharedPackageConfig}, utils::network::get_agent};
+use crate::{data::{config::Config, package::SharedPackageConfig}, utils::network::get_agent};
 static API_URL: &str = "https://qpackages.com";
 
+/// The configured registry, read once as it is needed for every request
+static REGISTRY_URL: Lazy<String> = Lazy::new(|| {
+    Config::read_combine()
+        .registry
+        .unwrap_or_else(|| API_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+});
+
+/// The registry to use, qpackages.com unless another one is configured
+pub fn api_url() -> &'static str {
+    &REGISTRY_URL
+}
+
 static VERSIONS_CACHE: Lazy<AtomicRefCell<HashMap<String, Vec<PackageVersion>>>> =
     Lazy::new(Default::default);
 static SHARED_PACKAGE_CACHE: Lazy<AtomicRefCell<HashMap<String, SharedPackageConfig>>> =
@@ -21,6 +35,12 @@ static SHARED_PACKAGE_CACHE: Lazy<AtomicRefCell<HashMap<String, SharedPackageCon
 pub struct PackageVersion {
     pub id: String,
     pub version: Version,
+    /// Yanked versions are broken, they are only used when they were already locked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yanked: Option<bool>,
+    /// Why the version was yanked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yanked_reason: Option<String>,
 }
 
 // true if 404
@@ -43,7 +63,7 @@ fn is_404_or_panic(res: &Result<Response, reqwest::Error>) -> bool {
 
 /// Requests the appriopriate package info from qpackage.com
 pub fn get_versions(id: &str) -> Option<Vec<PackageVersion>> {
-    let url = format!("{}/{}?limit=0", API_URL, id);
+    let url = format!("{}/{}?limit=0", api_url(), id);
 
     if let Some(entry) = VERSIONS_CACHE.borrow().get(&url) {
         return Some(entry.clone());
@@ -70,7 +90,7 @@ pub fn get_versions(id: &str) -> Option<Vec<PackageVersion>> {
 }
 
 pub fn get_shared_package(id: &str, ver: &Version) -> Option<SharedPackageConfig> {
-    let url = format!("{}/{}/{}", API_URL, id, ver);
+    let url = format!("{}/{}/{}", api_url(), id, ver);
 
     if let Some(entry) = SHARED_PACKAGE_CACHE.borrow().get(&url) {
         return Some(entry.clone());
@@ -93,7 +113,7 @@ pub fn get_shared_package(id: &str, ver: &Version) -> Option<SharedPackageConfig
 
 pub fn get_packages() -> Vec<String> {
     get_agent()
-        .get(API_URL)
+        .get(api_url())
         .send()
         .expect("Request to qpackages.com failed")
         .json()
@@ -103,7 +123,7 @@ pub fn get_packages() -> Vec<String> {
 pub fn publish_package(package: &SharedPackageConfig, auth: &str) {
     let url = format!(
         "{}/{}/{}",
-        API_URL, &package.config.info.id, &package.config.info.version
+        api_url(), &package.config.info.id, &package.config.info.version
     );
 
     let resp = get_agent()
@@ -114,7 +134,99 @@ pub fn publish_package(package: &SharedPackageConfig, auth: &str) {
         .expect("Request to qpackages.com failed");
     
     if resp.status() == StatusCode::UNAUTHORIZED {
-        panic!("Could not publish to {}: Unauthorized! Did you provide the correct key?", API_URL);
+        panic!("Could not publish to {}: Unauthorized! Did you provide the correct key?", api_url());
+    }
+    resp.error_for_status().expect("Response not OK!");
+}
+
+/// Marks a published version as yanked, or makes it available again
+pub fn yank_package(id: &str, version: &Version, yanked: bool, reason: Option<String>, auth: &str) {
+    let url = format!("{}/{}/{}/yank", api_url(), id, version);
+
+    let resp = get_agent()
+        .post(&url)
+        .header("Authorization", auth)
+        .json(&serde_json::json!({
+            "yanked": yanked,
+            "reason": reason,
+        }))
+        .send()
+        .expect("Request to qpackages.com failed");
+
+    if resp.status() == StatusCode::UNAUTHORIZED {
+        panic!("Could not yank on {}: Unauthorized! Did you provide the correct key?", api_url());
+    }
+    resp.error_for_status().expect("Response not OK!");
+
+    VERSIONS_CACHE.borrow_mut().clear();
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryUser {
+    pub username: String,
+}
+
+fn panic_if_unauthorized(resp: &Response) {
+    if resp.status() == StatusCode::UNAUTHORIZED {
+        panic!("{}: Unauthorized! Did you provide the correct key?", api_url());
+    }
+}
+
+/// The user the key belongs to, None if the registry does not accept the key
+pub fn whoami(auth: &str) -> Option<RegistryUser> {
+    let resp = get_agent()
+        .get(format!("{}/user", api_url()))
+        .header("Authorization", auth)
+        .send()
+        .expect("Request to registry failed");
+
+    if resp.status() == StatusCode::UNAUTHORIZED {
+        return None;
     }
+
+    Some(
+        resp.error_for_status()
+            .expect("Response not OK!")
+            .json()
+            .expect("Into json failed"),
+    )
+}
+
+/// The users that may publish a package id
+pub fn get_owners(id: &str, auth: &str) -> Vec<String> {
+    let resp = get_agent()
+        .get(format!("{}/{}/owners", api_url(), id))
+        .header("Authorization", auth)
+        .send()
+        .expect("Request to registry failed");
+
+    panic_if_unauthorized(&resp);
+    resp.error_for_status()
+        .expect("Response not OK!")
+        .json()
+        .expect("Into json failed")
+}
+
+pub fn add_owner(id: &str, user: &str, auth: &str) {
+    let resp = get_agent()
+        .post(format!("{}/{}/owners", api_url(), id))
+        .header("Authorization", auth)
+        .json(&serde_json::json!({ "username": user }))
+        .send()
+        .expect("Request to registry failed");
+
+    panic_if_unauthorized(&resp);
+    resp.error_for_status().expect("Response not OK!");
+}
+
+pub fn remove_owner(id: &str, user: &str, auth: &str) {
+    let resp = get_agent()
+        .delete(format!("{}/{}/owners/{}", api_url(), id, user))
+        .header("Authorization", auth)
+        .send()
+        .expect("Request to registry failed");
+
+    panic_if_unauthorized(&resp);
     resp.error_for_status().expect("Response not OK!");
 }
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use owo_colors::OwoColorize;
+use semver::Version;
+
+use crate::data::{
+    package::{PackageConfig, SharedPackageConfig},
+    qpackages::PackageVersion,
+};
+
+use super::DependencyRepository;
+
+/// Packages that are referenced through `localPath` on a dependency.
+///
+/// These are read straight from the `qpm.json` in that folder every time,
+/// so changes to the local package are picked up without a `qpm install`
+#[derive(Clone, Debug, Default)]
+pub struct LocalPathRepository {
+    packages: HashMap<String, (PathBuf, PackageConfig)>,
+}
+
+impl LocalPathRepository {
+    /// Collects all local path dependencies of this package, and of those local packages in turn
+    ///
+    /// Overrides with a local path are included as well
+    pub fn from_package(package: &PackageConfig) -> Self {
+        let mut repo = Self::default();
+        for (id, package_override) in package.overrides.iter() {
+            if let Some(local_path) = &package_override.local_path {
+                repo.add(id, PathBuf::from(local_path));
+            }
+        }
+        repo.collect(package, &PathBuf::new());
+        repo
+    }
+
+    /// Collects the local path dependencies that were recorded in a `qpm.shared.json`
+    pub fn from_restored(shared_package: &SharedPackageConfig) -> Self {
+        let mut repo = Self::default();
+        for restored in shared_package.restored_dependencies.iter() {
+            if let Some(local_path) = &restored.dependency.additional_data.local_path {
+                let local_path = PathBuf::from(local_path);
+                let local_package = PackageConfig::read_path(local_path.join("qpm.json"));
+                repo.packages
+                    .insert(restored.dependency.id.clone(), (local_path, local_package));
+            }
+        }
+        repo
+    }
+
+    fn collect(&mut self, package: &PackageConfig, base: &Path) {
+        for dependency in package.all_dependencies() {
+            if let Some(local_path) = &dependency.additional_data.local_path {
+                self.add(&dependency.id, base.join(local_path));
+            }
+        }
+    }
+
+    fn add(&mut self, id: &str, local_path: PathBuf) {
+        if self.packages.contains_key(id) {
+            return;
+        }
+
+        let package_path = local_path.join("qpm.json");
+        if !package_path.exists() {
+            panic!(
+                "Local dependency {} points to {}, but there is no qpm.json there!",
+                id.bright_red(),
+                local_path.display().bright_yellow()
+            );
+        }
+
+        let local_package = PackageConfig::read_path(package_path);
+        if local_package.info.id != id {
+            panic!(
+                "Local dependency {} points to {}, which contains package {} instead!",
+                id.bright_red(),
+                local_path.display().bright_yellow(),
+                local_package.info.id.bright_red()
+            );
+        }
+
+        self.packages
+            .insert(id.to_string(), (local_path.clone(), local_package.clone()));
+        // local packages can have local dependencies themselves, relative to their own folder
+        self.collect(&local_package, &local_path);
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.packages.contains_key(id)
+    }
+}
+
+impl DependencyRepository for LocalPathRepository {
+    fn get_versions(&self, id: &str) -> Option<Vec<PackageVersion>> {
+        self.packages.get(id).map(|(_, package)| {
+            vec![PackageVersion {
+                id: id.to_string(),
+                version: package.info.version.clone(),
+                yanked: None,
+                yanked_reason: None,
+            }]
+        })
+    }
+
+    fn get_shared_package(&self, id: &str, version: &Version) -> Option<SharedPackageConfig> {
+        let (path, package) = self.packages.get(id)?;
+        if package.info.version != *version {
+            return None;
+        }
+
+        let mut config = package.clone();
+        // remember where this came from, so restore knows to link it instead of caching it
+        config.info.additional_data.local_path = Some(path.display().to_string());
+
+        Some(SharedPackageConfig {
+            config,
+            restored_dependencies: Vec::new(),
+        })
+    }
+}
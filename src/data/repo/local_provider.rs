@@ -13,6 +13,8 @@ impl DependencyRepository for FileRepository {
                 .map(|version| PackageVersion {
                     id: id.to_string(),
                     version: version.clone(),
+                    yanked: None,
+                    yanked_reason: None,
                 })
                 .collect()
         })
@@ -1,6 +1,7 @@
 use super::{package::SharedPackageConfig, qpackages::{PackageVersion}, dependency::SharedDependency};
 
 
+pub mod local_path_provider;
 pub mod local_provider;
 pub mod qpm_provider;
 pub mod multi_provider;
@@ -1,8 +1,8 @@
 use itertools::Itertools;
 
-use crate::data::{package::{SharedPackageConfig}, file_repository::FileRepository, qpackages::PackageVersion};
+use crate::data::{package::SharedPackageConfig, file_repository::FileRepository, qpackages::PackageVersion};
 
-use super::{DependencyRepository, qpm_provider::QPMRepository};
+use super::{DependencyRepository, local_path_provider::LocalPathRepository, qpm_provider::QPMRepository};
 
 
 pub fn default_repositories() -> Vec<Box<dyn DependencyRepository>> {
@@ -25,6 +25,14 @@ impl MultiDependencyProvider {
     pub fn useful_default_new() -> Self {
         MultiDependencyProvider::new(default_repositories())
     }
+
+    /// The default repositories, preceded by the local path dependencies restored for this package
+    pub fn for_shared_package(shared_package: &SharedPackageConfig) -> Self {
+        let mut repositories: Vec<Box<dyn DependencyRepository>> =
+            vec![Box::new(LocalPathRepository::from_restored(shared_package))];
+        repositories.append(&mut default_repositories());
+        MultiDependencyProvider::new(repositories)
+    }
 }
 
 /// 
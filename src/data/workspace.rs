@@ -0,0 +1,243 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use owo_colors::OwoColorize;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{
+        dependency::{AdditionalDependencyData, Dependency, SharedDependency},
+        package::{PackageConfig, PackageInfo, SharedPackageConfig},
+    },
+    resolver::{self, ResolutionStrategy},
+};
+
+/// id used for the package that depends on every member while resolving a workspace
+static WORKSPACE_ROOT_ID: &str = "qpm-workspace-root";
+
+/// A `qpm.workspace.json` groups multiple packages of one repository together,
+/// so they can be restored, installed and published in one go
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceConfig {
+    /// Folders containing a qpm.json, relative to the workspace root
+    pub members: Vec<PathBuf>,
+}
+
+impl WorkspaceConfig {
+    pub fn check() -> bool {
+        Path::new("qpm.workspace.json").exists()
+    }
+
+    pub fn read() -> WorkspaceConfig {
+        let file =
+            std::fs::File::open("qpm.workspace.json").expect("Opening qpm.workspace.json failed");
+        serde_json::from_reader(file).expect("Deserializing workspace failed")
+    }
+
+    /// Reads the packages of all members, members are sorted so that they come after any member they depend on
+    pub fn read_members(&self) -> Vec<(PathBuf, PackageConfig)> {
+        let members: Vec<(PathBuf, PackageConfig)> = self
+            .members
+            .iter()
+            .map(|path| {
+                let package_path = path.join("qpm.json");
+                if !package_path.exists() {
+                    panic!(
+                        "Workspace member {} does not contain a qpm.json!",
+                        path.display().bright_yellow()
+                    );
+                }
+                (path.clone(), PackageConfig::read_path(package_path))
+            })
+            .collect();
+
+        let ids: HashMap<String, usize> = members
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, package))| (package.info.id.clone(), idx))
+            .collect();
+
+        fn visit(
+            idx: usize,
+            members: &[(PathBuf, PackageConfig)],
+            ids: &HashMap<String, usize>,
+            visiting: &mut HashSet<usize>,
+            order: &mut Vec<usize>,
+        ) {
+            if order.contains(&idx) {
+                return;
+            }
+            if !visiting.insert(idx) {
+                panic!(
+                    "Workspace member {} is part of a dependency cycle!",
+                    members[idx].1.info.id.bright_red()
+                );
+            }
+
+            for dependency in members[idx].1.all_dependencies() {
+                if let Some(dep_idx) = ids.get(&dependency.id) {
+                    visit(*dep_idx, members, ids, visiting, order);
+                }
+            }
+
+            visiting.remove(&idx);
+            order.push(idx);
+        }
+
+        let mut order = Vec::new();
+        for idx in 0..members.len() {
+            visit(idx, &members, &ids, &mut HashSet::new(), &mut order);
+        }
+
+        order.into_iter().map(|idx| members[idx].clone()).collect()
+    }
+
+    /// Resolves all members together, so they all agree on the versions of shared dependencies
+    ///
+    /// Members that depend on each other always resolve to the member folder
+    pub fn resolve(&self, strategy: ResolutionStrategy) -> Vec<(PathBuf, SharedPackageConfig)> {
+        let members = self.read_members();
+        let member_ids: HashSet<String> = members
+            .iter()
+            .map(|(_, package)| package.info.id.clone())
+            .collect();
+
+        let root = PackageConfig {
+            shared_dir: PathBuf::from("shared"),
+            dependencies_dir: PathBuf::from("extern"),
+            info: PackageInfo {
+                name: "Workspace".to_string(),
+                id: WORKSPACE_ROOT_ID.to_string(),
+                version: Version::new(0, 0, 0),
+                url: None,
+                description: None,
+                game: None,
+                additional_data: Default::default(),
+            },
+            dependencies: members
+                .iter()
+                .map(|(path, package)| Dependency {
+                    id: package.info.id.clone(),
+                    version_range: VersionReq::parse(&format!("={}", package.info.version))
+                        .unwrap(),
+                    additional_data: AdditionalDependencyData {
+                        local_path: Some(path.display().to_string()),
+                        ..Default::default()
+                    },
+                })
+                .collect(),
+            dev_dependencies: Vec::new(),
+            additional_data: Default::default(),
+            features: Default::default(),
+            // overrides of any member apply to the whole workspace
+            overrides: members
+                .iter()
+                .flat_map(|(path, package)| {
+                    package.overrides.iter().map(move |(id, package_override)| {
+                        let mut package_override = package_override.clone();
+                        if let Some(local_path) = package_override.local_path.take() {
+                            package_override.local_path =
+                                Some(path.join(local_path).display().to_string());
+                        }
+                        (id.clone(), package_override)
+                    })
+                })
+                .collect(),
+            targets: Default::default(),
+        };
+
+        // the locks of all members, a workspace is restored all at once so they should agree anyway
+        let locked = members
+            .iter()
+            .flat_map(|(path, _)| resolver::locked_versions(path))
+            .collect();
+
+        let resolved: HashMap<String, SharedPackageConfig> =
+            resolver::resolve_workspace(&root, member_ids, strategy, locked)
+                .map(|shared_package| (shared_package.config.info.id.clone(), shared_package))
+                .collect();
+
+        members
+            .iter()
+            .map(|(path, package)| {
+                // only ever use the versions that were resolved for everyone
+                let non_dev: HashSet<&str> =
+                    resolver::reachable(package.dependencies.iter(), &resolved)
+                        .into_iter()
+                        .map(|cfg| cfg.config.info.id.as_str())
+                        .collect();
+                let mut locked_private = resolver::locked_private_versions(path);
+
+                let restored_dependencies = resolver::reachable(package.all_dependencies(), &resolved)
+                    .into_iter()
+                    .map(|cfg| {
+                        let mut shared_package = cfg.clone();
+                        // local paths are relative to the workspace root, restore runs from within the member
+                        if let Some(local_path) =
+                            shared_package.config.info.additional_data.local_path.take()
+                        {
+                            shared_package.config.info.additional_data.local_path =
+                                Some(relative_to_member(path, Path::new(&local_path)));
+                        }
+
+                        // overrides of every member were used to resolve, so they apply to every member
+                        let mut shared_dep =
+                            SharedDependency::for_package(package, &root.overrides, shared_package);
+                        if !non_dev.contains(cfg.config.info.id.as_str()) {
+                            shared_dep.dev = Some(true);
+                        }
+                        shared_dep.private_dependencies = resolver::resolve_private(
+                            cfg,
+                            strategy,
+                            locked_private.remove(&cfg.config.info.id).unwrap_or_default(),
+                        );
+                        shared_dep
+                    })
+                    .collect();
+
+                (
+                    path.clone(),
+                    SharedPackageConfig {
+                        config: package.clone(),
+                        restored_dependencies,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Runs an operation from within the folder of a member
+    pub fn in_member<T>(member: &Path, operation: impl FnOnce() -> T) -> T {
+        let root = std::env::current_dir().expect("No current path found? what?");
+        println!(
+            "\nIn workspace member {}",
+            member.display().bright_yellow()
+        );
+        std::env::set_current_dir(root.join(member)).unwrap_or_else(|e| {
+            panic!(
+                "Failed to enter workspace member {}: {}",
+                member.display().bright_yellow(),
+                e
+            )
+        });
+        let result = operation();
+        std::env::set_current_dir(root).expect("Failed to return to the workspace root");
+        result
+    }
+}
+
+/// Turns a path relative to the workspace root into one relative to the member folder
+fn relative_to_member(member: &Path, path: &Path) -> String {
+    member
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .map(|_| Component::ParentDir)
+        .collect::<PathBuf>()
+        .join(path)
+        .display()
+        .to_string()
+}
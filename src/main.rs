@@ -33,16 +33,30 @@ enum MainCommand {
     Dependency(commands::dependency::Dependency),
     /// Package control
     Package(commands::package::Package),
+    /// Show a published package and what it was published with
+    Info(commands::info::InfoOperation),
+    /// Set up a new mod in the current folder from a template
+    Init(commands::init::InitOperation),
     /// List all properties that are currently supported by QPM
     List(commands::list::ListOperation),
+    /// Show which dependencies have newer versions available
+    Outdated,
     /// Publish package
     Publish(commands::publish::Publish),
     /// Restore and resolve all dependencies from the package
-    Restore,
+    Restore(commands::restore::RestoreOperation),
+    /// Search the registry for packages
+    Search(commands::search::SearchOperation),
     /// Qmod control
     Qmod(commands::qmod::Qmod),
+    /// Find the packages that depend on a package
+    Rdeps(commands::rdeps::RdepsOperation),
+    /// Registry account control
+    Registry(commands::registry::Registry),
     /// Install to local repository
     Install(commands::install::InstallOperation),
+    /// Mark a published version as broken, so it is no longer picked by restore
+    Yank(commands::yank::YankOperation),
 }
 
 fn main() {
@@ -55,11 +69,18 @@ fn main() {
         MainCommand::Config(c) => commands::config::execute_config_operation(c),
         MainCommand::Dependency(d) => commands::dependency::execute_dependency_operation(d),
         MainCommand::Package(p) => commands::package::execute_package_operation(p),
+        MainCommand::Info(i) => commands::info::execute_info_operation(i),
+        MainCommand::Init(i) => commands::init::execute_init_operation(i),
         MainCommand::List(l) => commands::list::execute_list_operation(l),
+        MainCommand::Outdated => commands::outdated::execute_outdated_operation(),
         MainCommand::Publish(a) => commands::publish::execute_publish_operation(&a),
-        MainCommand::Restore => commands::restore::execute_restore_operation(),
+        MainCommand::Restore(r) => commands::restore::execute_restore_operation(r),
+        MainCommand::Search(s) => commands::search::execute_search_operation(s),
         MainCommand::Qmod(q) => commands::qmod::execute_qmod_operation(q),
+        MainCommand::Rdeps(r) => commands::rdeps::execute_rdeps_operation(r),
+        MainCommand::Registry(r) => commands::registry::execute_registry_operation(r),
         MainCommand::Install(i) => commands::install::execute_install_operation(i),
+        MainCommand::Yank(y) => commands::yank::execute_yank_operation(y),
     }
 }
 
@@ -1,5 +1,12 @@
-use std::process;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    process,
+};
+
+use clap::ValueEnum;
 
+use owo_colors::OwoColorize;
 use pubgrub::{
     error::PubGrubError,
     report::{DefaultStringReporter, Reporter},
@@ -7,6 +14,7 @@ use pubgrub::{
 
 
 use crate::data::{
+    dependency::{Dependency, SharedDependency},
     package::{PackageConfig, SharedPackageConfig}, repo::{DependencyRepository, multi_provider::MultiDependencyProvider},
 };
 
@@ -16,28 +24,220 @@ mod provider;
 mod semver;
 
 
+/// Which version to pick when multiple versions satisfy the version ranges
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ResolutionStrategy {
+    /// Keep the versions locked in qpm.shared.json when possible, otherwise pick the highest
+    #[default]
+    Locked,
+    /// Always pick the highest version
+    Highest,
+    /// Always pick the lowest version
+    Lowest,
+    /// Pick the lowest version for direct dependencies, and the highest for anything else
+    LowestDirect,
+}
+
+pub fn resolve(
+    root: &PackageConfig,
+    strategy: ResolutionStrategy,
+) -> impl Iterator<Item = SharedPackageConfig> + '_ {
+    let provider = HackDependencyProvider::new(root, MultiDependencyProvider::useful_default_new())
+        .with_strategy(strategy, locked_versions(Path::new(".")));
+    solve(root, provider, "dependencies".to_string())
+}
+
+/// The versions restored by the qpm.shared.json in this folder, if there is one
+pub fn locked_versions(folder: &Path) -> HashMap<String, ::semver::Version> {
+    let path = folder.join("qpm.shared.json");
+    if !path.exists() {
+        return HashMap::new();
+    }
+
+    SharedPackageConfig::read_path(path)
+        .restored_dependencies
+        .into_iter()
+        .map(|restored| (restored.dependency.id, restored.version))
+        .collect()
+}
+
+/// The versions of the private dependencies of every package restored by the qpm.shared.json in this folder
+pub fn locked_private_versions(folder: &Path) -> HashMap<String, HashMap<String, ::semver::Version>> {
+    let path = folder.join("qpm.shared.json");
+    if !path.exists() {
+        return HashMap::new();
+    }
+
+    SharedPackageConfig::read_path(path)
+        .restored_dependencies
+        .into_iter()
+        .map(|restored| {
+            let private = restored
+                .private_dependencies
+                .into_iter()
+                .map(|private| (private.dependency.id, private.version))
+                .collect();
+            (restored.dependency.id, private)
+        })
+        .collect()
+}
+
+/// Resolves a workspace root, which depends on all of its members
+///
+/// Members keep their private dependencies, as each of them is a root of its own
+pub fn resolve_workspace(
+    root: &PackageConfig,
+    members: HashSet<String>,
+    strategy: ResolutionStrategy,
+    locked: HashMap<String, ::semver::Version>,
+) -> impl Iterator<Item = SharedPackageConfig> + '_ {
+    let provider = HackDependencyProvider::with_members(
+        root,
+        members,
+        MultiDependencyProvider::useful_default_new(),
+    )
+    .with_strategy(strategy, locked);
+    solve(root, provider, "workspace dependencies".to_string())
+}
+
+/// Resolves the private dependencies of a resolved package in a graph of their own
+///
+/// They are linked into that package, so they don't have to agree with the rest of the dependencies.
+/// `locked` are the versions its private dependencies were restored with before
+pub fn resolve_private(
+    shared_package: &SharedPackageConfig,
+    strategy: ResolutionStrategy,
+    locked: HashMap<String, ::semver::Version>,
+) -> Vec<SharedDependency> {
+    // local packages are built from their own folder, with their own restore
+    if shared_package.config.info.additional_data.local_path.is_some() {
+        return Vec::new();
+    }
+
+    let mut package = shared_package.config.clone();
+    let features = package
+        .info
+        .additional_data
+        .features
+        .clone()
+        .unwrap_or_default();
+    package.dependencies = shared_package
+        .config
+        .dependencies
+        .iter()
+        .filter(|dep| shared_package.config.is_dependency_enabled(dep, &features))
+        .cloned()
+        .collect();
+    package.dev_dependencies.clear();
+
+    let is_private = |dep: &&Dependency| dep.additional_data.is_private.unwrap_or(false);
+    if !package.dependencies.iter().any(|dep| is_private(&dep)) {
+        return Vec::new();
+    }
+
+    let provider = HackDependencyProvider::new(&package, MultiDependencyProvider::useful_default_new())
+        .with_strategy(strategy, locked);
+    let description = format!(
+        "private dependencies of {} {}",
+        package.info.id, package.info.version
+    );
+    let resolved: HashMap<String, SharedPackageConfig> = solve(&package, provider, description)
+        .map(|cfg| (cfg.config.info.id.clone(), cfg))
+        .collect();
 
-pub fn resolve(root: &PackageConfig) -> impl Iterator<Item = SharedPackageConfig> + '_ {
-    let provider = HackDependencyProvider::new(root, MultiDependencyProvider::useful_default_new());
+    reachable(package.dependencies.iter().filter(is_private), &resolved)
+        .into_iter()
+        .map(|cfg| SharedDependency::for_package(&package, &package.overrides, cfg.clone()))
+        .collect()
+}
+
+fn solve<'a>(
+    root: &'a PackageConfig,
+    provider: HackDependencyProvider<'a>,
+    description: String,
+) -> impl Iterator<Item = SharedPackageConfig> + 'a {
     match pubgrub::solver::resolve(&provider, root.info.id.clone(), root.info.version.clone()) {
-        Ok(deps) => deps
-            .into_iter()
-            .filter_map(move |(id, version)| {
+        Ok(deps) => {
+            // collect which features ended up enabled for every package
+            let mut features: HashMap<String, Vec<String>> = HashMap::new();
+            for id in deps.keys() {
+                if let Some((package_id, feature)) = provider::split_feature_package_id(id) {
+                    features
+                        .entry(package_id.to_string())
+                        .or_default()
+                        .push(feature.to_string());
+                }
+            }
+
+            deps.into_iter().filter_map(move |(id, version)| {
                 if id == root.info.id && version == root.info.version {
                     return None;
                 }
-        
-                provider.get_shared_package(&id, &version.into())
-            }),
+                if provider::split_feature_package_id(&id).is_some() {
+                    return None;
+                }
+
+                let mut shared_package = provider.get_shared_package(&id, &version.into())?;
+                if let Some(mut enabled) = features.remove(&id) {
+                    enabled.sort();
+                    shared_package.config.info.additional_data.features = Some(enabled);
+                }
+                Some(shared_package)
+            })
+        }
 
         Err(PubGrubError::NoSolution(tree)) => {
             let report = DefaultStringReporter::report(&tree);
-            eprintln!("failed to resolve dependencies: \n{}", report);
+            eprintln!("failed to resolve {}: \n{}", description.bright_red(), report);
+            let rejected = provider.rejected.borrow();
+            if !rejected.is_empty() {
+                eprintln!("\nversions that can not be used because of the game version:");
+                for reason in rejected.iter() {
+                    eprintln!(" - {}", reason.bright_red());
+                }
+            }
             process::exit(1)
         }
         Err(err) => {
-            eprintln!("{}", err);
+            eprintln!("failed to resolve {}: {}", description.bright_red(), err);
             process::exit(1)
         }
     }
 }
+
+/// The resolved packages that can be reached from these dependencies, without following private dependencies of dependencies
+pub fn reachable<'a>(
+    dependencies: impl Iterator<Item = &'a Dependency>,
+    resolved: &'a HashMap<String, SharedPackageConfig>,
+) -> Vec<&'a SharedPackageConfig> {
+    let mut result = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue: Vec<&Dependency> = dependencies.collect();
+    while let Some(dependency) = queue.pop() {
+        if !visited.insert(dependency.id.as_str()) {
+            continue;
+        }
+
+        let shared_package = resolved.get(&dependency.id).unwrap_or_else(|| {
+            panic!(
+                "Dependency {} was not resolved",
+                dependency.id.bright_red()
+            )
+        });
+
+        let features = shared_package
+            .config
+            .info
+            .additional_data
+            .features
+            .as_deref()
+            .unwrap_or_default();
+        queue.extend(shared_package.config.dependencies.iter().filter(|dep| {
+            !dep.additional_data.is_private.unwrap_or(false)
+                && shared_package.config.is_dependency_enabled(dep, features)
+        }));
+        result.push(shared_package);
+    }
+
+    result
+}
@@ -1,24 +1,168 @@
-use std::borrow::Borrow;
+use std::{
+    borrow::Borrow,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
 
 use pubgrub::{range::Range, solver::Dependencies};
 
-use super::semver::{req_to_range, Version};
+use super::{
+    semver::{req_to_range, Version},
+    ResolutionStrategy,
+};
 use crate::data::{
-    package::{PackageConfig, SharedPackageConfig},
+    dependency::Dependency,
+    package::{GameTarget, PackageConfig, SharedPackageConfig},
     qpackages::{self, PackageVersion},
-    repo::{multi_provider::MultiDependencyProvider, DependencyRepository},
+    repo::{
+        local_path_provider::LocalPathRepository, multi_provider::MultiDependencyProvider,
+        DependencyRepository,
+    },
 };
 
 pub struct HackDependencyProvider<'a> {
     root: &'a PackageConfig,
+    /// packages that are resolved as if they were the root, so their private dependencies are kept
+    members: HashSet<String>,
+    local: LocalPathRepository,
     repo: MultiDependencyProvider,
+    strategy: ResolutionStrategy,
+    /// versions from an existing qpm.shared.json, preferred by the locked strategy
+    /// and the only yanked versions that may be picked
+    locked: HashMap<String, semver::Version>,
+    /// package versions that were skipped because they target another game version than the root, and why
+    pub(super) rejected: RefCell<Vec<String>>,
 }
 
 impl<'a> HackDependencyProvider<'a> {
     // Repositories sorted in order
     pub fn new(root: &'a PackageConfig, repo: MultiDependencyProvider) -> Self {
-        Self { root, repo }
+        Self::with_members(root, HashSet::new(), repo)
+    }
+
+    pub fn with_members(
+        root: &'a PackageConfig,
+        members: HashSet<String>,
+        repo: MultiDependencyProvider,
+    ) -> Self {
+        Self {
+            root,
+            members,
+            local: LocalPathRepository::from_package(root),
+            repo,
+            strategy: ResolutionStrategy::default(),
+            locked: HashMap::new(),
+            rejected: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn with_strategy(
+        mut self,
+        strategy: ResolutionStrategy,
+        locked: HashMap<String, semver::Version>,
+    ) -> Self {
+        self.strategy = strategy;
+        self.locked = locked;
+        self
+    }
+
+    /// Picks one of the versions that satisfy the range, according to the resolution strategy
+    fn pick_version(&self, id: &str, versions: Vec<Version>) -> Option<Version> {
+        let package_id = split_feature_package_id(id).map_or(id, |(package_id, _)| package_id);
+        let highest = |versions: Vec<Version>| versions.into_iter().max_by(Version::semver_cmp);
+        let lowest = |versions: Vec<Version>| versions.into_iter().min_by(Version::semver_cmp);
+        match self.strategy {
+            ResolutionStrategy::Highest => highest(versions),
+            ResolutionStrategy::Lowest => lowest(versions),
+            ResolutionStrategy::LowestDirect => {
+                if self.root.all_dependencies().any(|dep| dep.id == package_id) {
+                    lowest(versions)
+                } else {
+                    highest(versions)
+                }
+            }
+            ResolutionStrategy::Locked => {
+                let locked = self.locked.get(package_id);
+                match locked.and_then(|locked| versions.iter().find(|v| *v == locked)) {
+                    Some(version) => Some(version.clone()),
+                    None => highest(versions),
+                }
+            }
+        }
+    }
+
+    /// Whether the package targets a game version the root can run on, packages without a target work anywhere
+    fn is_game_compatible(&self, package: &SharedPackageConfig) -> bool {
+        let (root_game, game) = match (&self.root.info.game, &package.config.info.game) {
+            (Some(root_game), Some(game)) => (root_game, game),
+            _ => return true,
+        };
+
+        let compatible = root_game.id == game.id
+            && req_to_range(root_game.version_range.clone())
+                .intersection(&req_to_range(game.version_range.clone()))
+                != Range::none();
+        if !compatible {
+            self.rejected.borrow_mut().push(format!(
+                "{} {} targets {}, but {} targets {}",
+                package.config.info.id,
+                package.config.info.version,
+                describe_game(game),
+                self.root.info.id,
+                describe_game(root_game)
+            ));
+        }
+        compatible
     }
+
+    /// The range a dependency may resolve to, overrides of the root win over whatever range was asked for
+    fn dependency_range(&self, dependency: &Dependency) -> Range<Version> {
+        if let Some(package_override) = self.root.overrides.get(&dependency.id) {
+            if let Some(version) = &package_override.version {
+                return Range::exact(version.clone());
+            }
+
+            if package_override.local_path.is_some() {
+                if let Some(local_version) = self
+                    .local
+                    .get_versions(&dependency.id)
+                    .and_then(|versions| versions.into_iter().next())
+                {
+                    return Range::exact(local_version.version);
+                }
+            }
+        }
+
+        req_to_range(dependency.version_range.clone())
+    }
+
+    /// The dependency itself, and a feature package for every feature that it enables
+    fn dependency_entries(&self, dependency: &Dependency) -> Vec<(String, Range<Version>)> {
+        let range = self.dependency_range(dependency);
+        let mut entries: Vec<(String, Range<Version>)> = dependency
+            .additional_data
+            .features
+            .iter()
+            .flatten()
+            .map(|feature| (feature_package_id(&dependency.id, feature), range.clone()))
+            .collect();
+        entries.push((dependency.id.clone(), range));
+        entries
+    }
+}
+
+/// Features are resolved as a package of their own, named `id[feature]`.
+/// It depends on the exact same version of the package, and on the optional dependencies the feature enables
+fn feature_package_id(id: &str, feature: &str) -> String {
+    format!("{id}[{feature}]")
+}
+
+fn describe_game(game: &GameTarget) -> String {
+    format!("{} {}", game.id, game.version_range)
+}
+
+pub(super) fn split_feature_package_id(id: &str) -> Option<(&str, &str)> {
+    id.strip_suffix(']')?.split_once('[')
 }
 
 ///
@@ -33,10 +177,28 @@ impl DependencyRepository for HackDependencyProvider<'_> {
             return Some(vec![qpackages::PackageVersion {
                 id: self.root.info.id.clone(),
                 version: self.root.info.version.clone(),
+                yanked: None,
+                yanked_reason: None,
             }]);
         }
 
-        let result = self.repo.get_versions(id);
+        // a feature has exactly the versions of its package
+        if let Some((package_id, _)) = split_feature_package_id(id) {
+            return self.get_versions(package_id);
+        }
+
+        // local path dependencies only ever have the version that is on disk
+        if self.local.contains(id) {
+            return self.local.get_versions(id);
+        }
+
+        // yanked versions can only be used by those that already had them locked
+        let result = self.repo.get_versions(id).map(|mut versions| {
+            versions.retain(|v| {
+                !v.yanked.unwrap_or(false) || self.locked.get(id) == Some(&v.version)
+            });
+            versions
+        });
 
         if result.is_none() || result.as_ref().unwrap().is_empty() {
             return None;
@@ -51,7 +213,17 @@ impl DependencyRepository for HackDependencyProvider<'_> {
         id: &str,
         version: &semver::Version,
     ) -> Option<SharedPackageConfig> {
-        self.repo.get_shared_package(id, version)
+        let mut shared_package = if self.local.contains(id) {
+            self.local.get_shared_package(id, version)
+        } else {
+            self.repo.get_shared_package(id, version)
+        }?;
+
+        if let Some(package_override) = self.root.overrides.get(id) {
+            package_override.apply(&mut shared_package);
+        }
+
+        Some(shared_package)
     }
 }
 
@@ -60,16 +232,25 @@ impl pubgrub::solver::DependencyProvider<String, Version> for HackDependencyProv
         &self,
         potential_packages: impl Iterator<Item = (T, U)>,
     ) -> Result<(T, Option<Version>), Box<dyn std::error::Error>> {
-        Ok(pubgrub::solver::choose_package_with_fewest_versions(
-            |id| {
-                self.get_versions(id)
+        // like pubgrub's choose_package_with_fewest_versions, but the version is picked by the strategy
+        let (package, versions) = potential_packages
+            .map(|(package, range)| {
+                let id: &String = package.borrow();
+                let versions: Vec<Version> = self
+                    .get_versions(id)
                     // TODO: Anyhow
                     .unwrap_or_else(|| panic!("Unable to find versions for package {id}"))
                     .into_iter()
                     .map(|pv: qpackages::PackageVersion| pv.version.into())
-            },
-            potential_packages,
-        ))
+                    .filter(|v| range.borrow().contains(v))
+                    .collect();
+                (package, versions)
+            })
+            .min_by_key(|(_, versions)| versions.len())
+            .expect("potential_packages gave us an empty iterator");
+
+        let version = self.pick_version(package.borrow(), versions);
+        Ok((package, version))
     }
 
     fn get_dependencies(
@@ -78,38 +259,65 @@ impl pubgrub::solver::DependencyProvider<String, Version> for HackDependencyProv
         version: &Version,
     ) -> Result<Dependencies<String, Version>, Box<dyn std::error::Error>> {
         if id == &self.root.info.id && version == &self.root.info.version {
+            // the root always restores its optional dependencies, it needs them to build its features
             let deps = self
                 .root
+                .all_dependencies()
+                .flat_map(|dep| self.dependency_entries(dep))
+                .collect();
+            return Ok(Dependencies::Known(deps));
+        }
+
+        if let Some((package_id, feature_name)) = split_feature_package_id(id) {
+            let package = self
+                .get_shared_package(package_id, &version.clone().into())
+                .unwrap_or_else(|| panic!("Could not find package {package_id} with version {version}"));
+
+            // this version does not have the feature, so it can't be picked
+            let feature = match package.config.features.get(feature_name) {
+                Some(feature) => feature,
+                None => return Ok(Dependencies::Unknown),
+            };
+
+            let mut deps: Vec<(String, Range<Version>)> = package
+                .config
                 .dependencies
                 .iter()
-                .map(|dep| {
-                    let id = &dep.id;
-                    let version = req_to_range(dep.version_range.clone());
-                    (id.clone(), version)
-                })
+                .filter(|dep| feature.dependencies.contains(&dep.id))
+                .flat_map(|dep| self.dependency_entries(dep))
                 .collect();
-            Ok(Dependencies::Known(deps))
+            deps.push((package_id.to_string(), Range::exact(version.clone())));
+            return Ok(Dependencies::Known(deps.into_iter().collect()));
+        }
+
+        let mut package = self
+            .get_shared_package(id, &version.clone().into())
+            .unwrap_or_else(|| panic!("Could not find package {id} with version {version}"));
+        if !self.is_game_compatible(&package) {
+            return Ok(Dependencies::Unknown);
+        }
+
+        // remove any private dependencies, unless this is a workspace member, they are resolved on their own later
+        // dev dependencies are never seen by consumers, only members restore them
+        // optional dependencies are added by the feature packages that enable them
+        if !self.members.contains(id) {
+            package.config.dependencies.retain(|dep| {
+                !dep.additional_data.is_private.unwrap_or(false)
+                    && !dep.additional_data.optional.unwrap_or(false)
+            });
         } else {
-            let mut package = self
-                .get_shared_package(id, &version.clone().into())
-                .unwrap_or_else(|| panic!("Could not find package {id} with version {version}"));
-            // remove any private dependencies
             package
                 .config
                 .dependencies
-                .retain(|dep| !dep.additional_data.is_private.unwrap_or(false));
-
-            let deps = package
-                .config
-                .dependencies
-                .into_iter()
-                .map(|dep| {
-                    let id = dep.id;
-                    let version = req_to_range(dep.version_range);
-                    (id, version)
-                })
-                .collect();
-            Ok(Dependencies::Known(deps))
+                .append(&mut package.config.dev_dependencies);
         }
+
+        let deps = package
+            .config
+            .dependencies
+            .iter()
+            .flat_map(|dep| self.dependency_entries(dep))
+            .collect();
+        Ok(Dependencies::Known(deps))
     }
 }
@@ -1,310 +1,219 @@
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 
 use pubgrub::range::Range;
 use semver::{Comparator, Op, Prerelease, VersionReq};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// A version as the resolver sees it
+///
+/// Build metadata is ignored, and every release sorts before every prerelease.
+/// That way the releases matched by a range are one contiguous set of versions, as are the
+/// prereleases, which is needed because prereleases only match when a comparator opts in.
+/// Use `semver_cmp` to compare versions the usual way.
+#[derive(Clone)]
 pub(super) struct Version(semver::Version);
 
-pub(super) fn req_to_range(req: VersionReq) -> Range<Version> {
-    let mut range = Range::any();
-    for comparator in req.comparators {
-        let next = match comparator {
-            Comparator {
-                op: Op::Exact,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => exact_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::Exact,
-                major,
-                minor: Some(minor),
-                ..
-            } => exact_xy(major, minor),
-            Comparator {
-                op: Op::Exact,
-                major,
-                ..
-            } => exact_x(major),
-
-            Comparator {
-                op: Op::Greater,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => greater_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::Greater,
-                major,
-                minor: Some(minor),
-                ..
-            } => greater_xy(major, minor),
-            Comparator {
-                op: Op::Greater,
-                major,
-                ..
-            } => greater_x(major),
-
-            Comparator {
-                op: Op::GreaterEq,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => greater_eq_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::GreaterEq,
-                major,
-                minor: Some(minor),
-                ..
-            } => greater_eq_xy(major, minor),
-            Comparator {
-                op: Op::GreaterEq,
-                major,
-                ..
-            } => greater_eq_x(major),
-
-            Comparator {
-                op: Op::Less,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => less_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::Less,
-                major,
-                minor: Some(minor),
-                ..
-            } => less_xy(major, minor),
-            Comparator {
-                op: Op::Less,
-                major,
-                ..
-            } => less_x(major),
-
-            Comparator {
-                op: Op::LessEq,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => less_eq_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::LessEq,
-                major,
-                minor: Some(minor),
-                ..
-            } => less_eq_xy(major, minor),
-            Comparator {
-                op: Op::LessEq,
-                major,
-                ..
-            } => less_eq_x(major),
-
-            Comparator {
-                op: Op::Tilde,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => tilde_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::Tilde,
-                major,
-                minor: Some(minor),
-                ..
-            } => tilde_xy(major, minor),
-            Comparator {
-                op: Op::Tilde,
-                major,
-                ..
-            } => tilde_x(major),
-
-            Comparator {
-                op: Op::Caret,
-                major: 0,
-                minor: Some(0),
-                patch: Some(patch),
-                pre,
-            } => caret_00z(patch, pre),
-            Comparator {
-                op: Op::Caret,
-                major: 0,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => caret_0yz(minor, patch, pre),
-            Comparator {
-                op: Op::Caret,
-                major,
-                minor: Some(minor),
-                patch: Some(patch),
-                pre,
-            } => caret_xyz(major, minor, patch, pre),
-            Comparator {
-                op: Op::Caret,
-                major: 0,
-                minor: Some(0),
-                ..
-            } => caret_00(),
-            Comparator {
-                op: Op::Caret,
-                major,
-                minor: Some(minor),
-                ..
-            } => caret_xy(major, minor),
-            Comparator {
-                op: Op::Caret,
-                major,
-                ..
-            } => caret_x(major),
-
-            Comparator {
-                op: Op::Wildcard,
-                major,
-                minor: Some(minor),
-                ..
-            } => wildcard_xy(major, minor),
-            Comparator {
-                op: Op::Wildcard,
-                major,
-                ..
-            } => wildcard_x(major),
-
-            _ => unimplemented!(),
-        };
-        range = range.intersection(&next);
+impl Version {
+    /// Compares the versions in the usual semver order, which puts prereleases right before their release
+    pub(super) fn semver_cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+
+    fn key(&self) -> (bool, u64, u64, u64, &Prerelease) {
+        (
+            !self.0.pre.is_empty(),
+            self.0.major,
+            self.0.minor,
+            self.0.patch,
+            &self.0.pre,
+        )
     }
-    range
 }
 
-fn exact_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    Range::exact(semver::Version {
-        major,
-        minor,
-        patch,
-        pre,
-        build: Default::default(),
-    })
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
 }
-fn exact_xy(major: u64, minor: u64) -> Range<Version> {
-    greater_eq_xyz(major, minor, 0, Prerelease::EMPTY).intersection(&less_xyz(
-        major,
-        minor + 1,
-        0,
-        Prerelease::EMPTY,
-    ))
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
-fn exact_x(major: u64) -> Range<Version> {
-    greater_eq_xyz(major, 0, 0, Prerelease::EMPTY).intersection(&less_xyz(
-        major + 1,
-        0,
-        0,
-        Prerelease::EMPTY,
-    ))
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
 }
+impl Eq for Version {}
 
-fn greater_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    greater_eq_xyz(major, minor, patch, pre.clone())
-        .intersection(&exact_xyz(major, minor, patch, pre).negate())
-}
-fn greater_xy(major: u64, minor: u64) -> Range<Version> {
-    greater_eq_xyz(major, minor + 1, 0, Prerelease::EMPTY)
-}
-fn greater_x(major: u64) -> Range<Version> {
-    greater_eq_xyz(major + 1, 0, 0, Prerelease::EMPTY)
+/// Converts a version requirement to the same set of versions that `VersionReq::matches` accepts
+pub(super) fn req_to_range(req: VersionReq) -> Range<Version> {
+    let mut range = Range::any();
+    // prereleases only match when a comparator with the same major.minor.patch asks for a prerelease
+    let mut allowed = releases();
+    for comparator in req.comparators {
+        if let Comparator {
+            major,
+            minor: Some(minor),
+            patch: Some(patch),
+            pre,
+            ..
+        } = &comparator
+        {
+            if !pre.is_empty() {
+                allowed = allowed.union(&Range::between(
+                    first(*major, *minor, *patch),
+                    first(*major, *minor, *patch + 1),
+                ));
+            }
+        }
+
+        range = range.intersection(&comparator_to_range(comparator));
+    }
+    range.intersection(&allowed)
 }
 
-fn greater_eq_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    Range::higher_than(semver::Version {
+fn comparator_to_range(comparator: Comparator) -> Range<Version> {
+    let Comparator {
+        op,
         major,
         minor,
         patch,
         pre,
-        build: Default::default(),
-    })
-}
-fn greater_eq_xy(major: u64, minor: u64) -> Range<Version> {
-    greater_eq_xyz(major, minor, 0, Prerelease::EMPTY)
-}
-fn greater_eq_x(major: u64) -> Range<Version> {
-    greater_eq_xyz(major, 0, 0, Prerelease::EMPTY)
+    } = comparator;
+
+    match (op, minor, patch) {
+        (Op::Exact, Some(minor), Some(patch)) => Range::exact(version(major, minor, patch, pre)),
+        (Op::Exact | Op::Wildcard, Some(minor), _) => {
+            interval(first(major, minor, 0), first(major, minor + 1, 0), false)
+        }
+        (Op::Exact | Op::Wildcard, None, _) => {
+            interval(first(major, 0, 0), first(major + 1, 0, 0), false)
+        }
+
+        (Op::Greater, Some(minor), Some(patch)) => {
+            at_least(successor(&version(major, minor, patch, pre)), true)
+        }
+        (Op::Greater, Some(minor), None) => at_least(first(major, minor + 1, 0), true),
+        (Op::Greater, None, _) => at_least(first(major + 1, 0, 0), true),
+
+        (Op::GreaterEq, Some(minor), Some(patch)) => {
+            at_least(version(major, minor, patch, pre), true)
+        }
+        (Op::GreaterEq, Some(minor), None) => at_least(first(major, minor, 0), false)
+            .union(&at_least(first(major, minor + 1, 0), true)),
+        (Op::GreaterEq, None, _) => {
+            at_least(first(major, 0, 0), false).union(&at_least(first(major + 1, 0, 0), true))
+        }
+
+        (Op::Less, Some(minor), Some(patch)) => below(version(major, minor, patch, pre), true),
+        (Op::Less, Some(minor), None) => below(first(major, minor, 0), true),
+        (Op::Less, None, _) => below(first(major, 0, 0), true),
+
+        (Op::LessEq, Some(minor), Some(patch)) => {
+            below(successor(&version(major, minor, patch, pre)), true)
+        }
+        (Op::LessEq, Some(minor), None) => {
+            below(first(major, minor + 1, 0), false).union(&below(first(major, minor, 0), true))
+        }
+        (Op::LessEq, None, _) => {
+            below(first(major + 1, 0, 0), false).union(&below(first(major, 0, 0), true))
+        }
+
+        (Op::Tilde, Some(minor), Some(patch)) => interval(
+            version(major, minor, patch, pre),
+            first(major, minor + 1, 0),
+            true,
+        ),
+        (Op::Tilde, Some(minor), None) => {
+            interval(first(major, minor, 0), first(major, minor + 1, 0), false)
+        }
+        (Op::Tilde, None, _) => interval(first(major, 0, 0), first(major + 1, 0, 0), false),
+
+        (Op::Caret, Some(minor), Some(patch)) => {
+            let upper = match (major, minor) {
+                (0, 0) => first(0, 0, patch + 1),
+                (0, _) => first(0, minor + 1, 0),
+                _ => first(major + 1, 0, 0),
+            };
+            interval(version(major, minor, patch, pre), upper, true)
+        }
+        (Op::Caret, Some(minor), None) => {
+            let upper = match major {
+                0 => first(0, minor + 1, 0),
+                _ => first(major + 1, 0, 0),
+            };
+            interval(first(major, minor, 0), upper, true)
+        }
+        (Op::Caret, None, _) => interval(first(major, 0, 0), first(major + 1, 0, 0), true),
+
+        (op, _, _) => panic!("Unsupported version requirement operator {op:?}"),
+    }
 }
 
-fn less_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    Range::strictly_lower_than(semver::Version {
+fn version(major: u64, minor: u64, patch: u64, pre: Prerelease) -> semver::Version {
+    semver::Version {
         major,
         minor,
         patch,
         pre,
         build: Default::default(),
-    })
-}
-fn less_xy(major: u64, minor: u64) -> Range<Version> {
-    less_xyz(major, minor, 0, Prerelease::EMPTY)
-}
-fn less_x(major: u64) -> Range<Version> {
-    less_xyz(major, 0, 0, Prerelease::EMPTY)
+    }
 }
 
-fn less_eq_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    less_xyz(major, minor, patch, pre.clone()).union(&exact_xyz(major, minor, patch, pre))
-}
-fn less_eq_xy(major: u64, minor: u64) -> Range<Version> {
-    less_xyz(major, minor + 1, 0, Prerelease::EMPTY)
-}
-fn less_eq_x(major: u64) -> Range<Version> {
-    less_xyz(major + 1, 0, 0, Prerelease::EMPTY)
+/// The lowest prerelease of a major.minor.patch, every other version with it sorts after it in the usual order
+fn first(major: u64, minor: u64, patch: u64) -> semver::Version {
+    version(major, minor, patch, Prerelease::new("0").unwrap())
 }
 
-fn tilde_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    greater_eq_xyz(major, minor, patch, pre).intersection(&less_xyz(
-        major,
-        minor + 1,
-        0,
-        Prerelease::EMPTY,
-    ))
-}
-fn tilde_xy(major: u64, minor: u64) -> Range<Version> {
-    exact_xy(major, minor)
-}
-fn tilde_x(major: u64) -> Range<Version> {
-    exact_x(major)
+/// The next version in the usual semver order, ignoring build metadata
+fn successor(version: &semver::Version) -> semver::Version {
+    if version.pre.is_empty() {
+        first(version.major, version.minor, version.patch + 1)
+    } else {
+        // adding an identifier makes a prerelease bigger, and `0` is the smallest identifier
+        let pre = Prerelease::new(&format!("{}.0", version.pre)).unwrap();
+        self::version(version.major, version.minor, version.patch, pre)
+    }
 }
 
-fn caret_00z(patch: u64, pre: Prerelease) -> Range<Version> {
-    exact_xyz(0, 0, patch, pre)
-}
-fn caret_0yz(minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    greater_eq_xyz(0, minor, patch, pre).intersection(&less_xyz(0, minor + 1, 0, Prerelease::EMPTY))
-}
-fn caret_xyz(major: u64, minor: u64, patch: u64, pre: Prerelease) -> Range<Version> {
-    greater_eq_xyz(major, minor, patch, pre).intersection(&less_xyz(
-        major + 1,
-        0,
-        0,
-        Prerelease::EMPTY,
-    ))
-}
-fn caret_00() -> Range<Version> {
-    exact_xy(0, 0)
+/// Every version that is not a prerelease
+fn releases() -> Range<Version> {
+    Range::strictly_lower_than(first(0, 0, 0))
 }
-fn caret_xy(major: u64, minor: u64) -> Range<Version> {
-    caret_xyz(major, minor, 0, Prerelease::EMPTY)
+
+/// The versions `lower <= v < upper` in the usual semver order, including prereleases if asked for
+fn interval(lower: semver::Version, upper: semver::Version, pre: bool) -> Range<Version> {
+    at_least(lower, pre).intersection(&below(upper, pre))
 }
-fn caret_x(major: u64) -> Range<Version> {
-    exact_x(major)
+
+/// The versions `lower <= v` in the usual semver order, including prereleases if asked for
+fn at_least(lower: semver::Version, pre: bool) -> Range<Version> {
+    let lowest_release = version(lower.major, lower.minor, lower.patch, Prerelease::EMPTY);
+    let mut range = Range::between(lowest_release, first(0, 0, 0));
+    if pre {
+        range = range.union(&Range::higher_than(lowest_prerelease(lower)));
+    }
+    range
 }
 
-fn wildcard_xy(major: u64, minor: u64) -> Range<Version> {
-    exact_xy(major, minor)
+/// The versions `v < upper` in the usual semver order, including prereleases if asked for
+fn below(upper: semver::Version, pre: bool) -> Range<Version> {
+    let lowest_release = version(upper.major, upper.minor, upper.patch, Prerelease::EMPTY);
+    let mut range = Range::strictly_lower_than(lowest_release);
+    if pre {
+        range = range.union(&Range::between(first(0, 0, 0), lowest_prerelease(upper)));
+    }
+    range
 }
-fn wildcard_x(major: u64) -> Range<Version> {
-    exact_x(major)
+
+/// The lowest prerelease that is not lower than this version in the usual semver order
+fn lowest_prerelease(version: semver::Version) -> semver::Version {
+    if version.pre.is_empty() {
+        successor(&version)
+    } else {
+        version
+    }
 }
 
 impl pubgrub::version::Version for Version {
@@ -313,9 +222,15 @@ impl pubgrub::version::Version for Version {
     }
 
     fn bump(&self) -> Self {
-        let mut v = self.0.clone();
-        v.patch += 1;
-        Self(v)
+        if self.0.pre.is_empty() {
+            Self(semver::Version::new(
+                self.0.major,
+                self.0.minor,
+                self.0.patch + 1,
+            ))
+        } else {
+            Self(successor(&self.0))
+        }
     }
 }
 
@@ -344,10 +259,111 @@ macro_rules! impl_traits {
             }
             impl PartialEq<$tt> for $t {
                 fn eq(&self, other: &$tt) -> bool {
-                    self.0.eq(other)
+                    self.0.major == other.major
+                        && self.0.minor == other.minor
+                        && self.0.patch == other.patch
+                        && self.0.pre == other.pre
                 }
             }
         )*
     };
 }
 impl_traits!(Version => semver::Version);
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use pubgrub::version::Version as _;
+
+    use super::*;
+
+    fn arb_pre() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just(String::new()),
+            Just("0".to_string()),
+            Just("alpha".to_string()),
+            Just("alpha.1".to_string()),
+            Just("beta".to_string()),
+            Just("rc.1".to_string()),
+            Just("rc.1.0".to_string()),
+        ]
+    }
+
+    fn arb_version() -> impl Strategy<Value = semver::Version> {
+        (0..3u64, 0..3u64, 0..3u64, arb_pre(), any::<bool>()).prop_map(
+            |(major, minor, patch, pre, build)| {
+                let mut version = format!("{major}.{minor}.{patch}");
+                if !pre.is_empty() {
+                    version += &format!("-{pre}");
+                }
+                if build {
+                    version += "+build.1";
+                }
+                semver::Version::parse(&version).unwrap()
+            },
+        )
+    }
+
+    fn arb_comparator() -> impl Strategy<Value = String> {
+        (
+            prop::sample::select(vec!["=", ">", ">=", "<", "<=", "~", "^", ""]),
+            0..3u64,
+            prop::option::of(0..3u64),
+            prop::option::of(0..3u64),
+            arb_pre(),
+            any::<bool>(),
+        )
+            .prop_map(|(op, major, minor, patch, pre, wildcard)| {
+                match (minor, patch) {
+                    (Some(minor), Some(patch)) if !pre.is_empty() => {
+                        format!("{op}{major}.{minor}.{patch}-{pre}")
+                    }
+                    (Some(minor), Some(patch)) => format!("{op}{major}.{minor}.{patch}"),
+                    (Some(minor), None) if wildcard => format!("{major}.{minor}.*"),
+                    (Some(minor), None) => format!("{op}{major}.{minor}"),
+                    (None, _) if wildcard => format!("{major}.*"),
+                    (None, _) => format!("{op}{major}"),
+                }
+            })
+    }
+
+    fn arb_req() -> impl Strategy<Value = VersionReq> {
+        prop::collection::vec(arb_comparator(), 1..4)
+            .prop_filter_map("invalid requirement", |comparators| {
+                VersionReq::parse(&comparators.join(", ")).ok()
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2048))]
+
+        #[test]
+        fn range_matches_like_version_req(req in arb_req(), version in arb_version()) {
+            let range = req_to_range(req.clone());
+            prop_assert_eq!(
+                range.contains(&Version(version.clone())),
+                req.matches(&version),
+                "{} with {}: {}", req, version, range
+            );
+        }
+
+        #[test]
+        fn exact_range_ignores_build_metadata(version in arb_version(), other in arb_version()) {
+            let range = Range::exact(version.clone());
+            let same = version.major == other.major
+                && version.minor == other.minor
+                && version.patch == other.patch
+                && version.pre == other.pre;
+            prop_assert_eq!(range.contains(&Version(other)), same);
+        }
+
+        #[test]
+        fn bump_is_the_next_version(version in arb_version(), other in arb_version()) {
+            let version = Version(version);
+            let other = Version(other);
+            let bumped = version.bump();
+            prop_assert!(bumped > version);
+            prop_assert!(!(other > version && other < bumped));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use goblin::elf::Elf;
+use owo_colors::OwoColorize;
+
+/// Libraries the android system provides, they never come from a qmod or dependency
+pub const SYSTEM_LIBRARIES: &[&str] = &[
+    "libc.so",
+    "libm.so",
+    "libdl.so",
+    "liblog.so",
+    "libz.so",
+    "libandroid.so",
+    "libEGL.so",
+    "libGLESv2.so",
+    "libGLESv3.so",
+    "libOpenSLES.so",
+    "libvulkan.so",
+    "libmediandk.so",
+    // the c++ standard library is loaded by the game before any mod
+    "libc++_shared.so",
+    "libstdc++.so",
+];
+
+/// What a shared object says about itself and what it links
+#[derive(Debug, Clone, Default)]
+pub struct SharedObjectInfo {
+    /// DT_SONAME, the name other libraries link it by
+    pub soname: Option<String>,
+    /// DT_NEEDED, the libraries it links
+    pub needed: Vec<String>,
+}
+
+impl SharedObjectInfo {
+    /// The linked libraries that are not provided by the system
+    pub fn needed_libraries(&self) -> impl Iterator<Item = &String> {
+        self.needed
+            .iter()
+            .filter(|name| !SYSTEM_LIBRARIES.contains(&name.as_str()))
+    }
+}
+
+/// Reads the dynamic section of a shared object, None if it is not an elf file
+pub fn read_shared_object(bytes: &[u8]) -> Option<SharedObjectInfo> {
+    let elf = Elf::parse(bytes).ok()?;
+    Some(SharedObjectInfo {
+        soname: elf.soname.map(str::to_string),
+        needed: elf.libraries.iter().map(|name| name.to_string()).collect(),
+    })
+}
+
+/// Reads the dynamic section of a shared object on disk, panics when it can not be read
+pub fn read_shared_object_file(path: &std::path::Path) -> SharedObjectInfo {
+    let bytes = std::fs::read(path)
+        .unwrap_or_else(|_| panic!("Failed to read {}", path.display().bright_red()));
+    read_shared_object(&bytes)
+        .unwrap_or_else(|| panic!("{} is not an elf file", path.display().bright_red()))
+}
@@ -161,6 +161,31 @@ pub fn clone(mut url: String, branch: Option<&String>, out: &std::path::Path) ->
     out.exists()
 }
 
+/// Whether the repository at url has a tag with this name, without cloning it
+pub fn remote_has_tag(mut url: String, tag: &str) -> bool {
+    check_git();
+    if let Ok(token_unwrapped) = get_keyring().get_password() {
+        if let Some(gitidx) = url.find("github.com") {
+            url.insert_str(gitidx, &format!("{}@", token_unwrapped));
+        }
+    }
+
+    if url.ends_with('/') {
+        url = url[..url.len() - 1].to_string();
+    }
+
+    // exits with 2 when the ref does not exist
+    std::process::Command::new("git")
+        .arg("ls-remote")
+        .arg("--exit-code")
+        .arg("--tags")
+        .arg(format!("{}.git", url))
+        .arg(format!("refs/tags/{}", tag))
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GithubReleaseAsset {
     pub url: String,
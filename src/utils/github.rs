@@ -0,0 +1,233 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use reqwest::{blocking::RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::network::get_agent;
+
+pub static GITHUB_API_URL: &str = "https://api.github.com";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GithubRelease {
+    pub id: u64,
+    pub tag_name: String,
+    /// Templated url, ends with `{?name,label}`
+    pub upload_url: String,
+    pub assets: Vec<GithubAsset>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GithubAsset {
+    pub id: u64,
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// Talks to the github release api, the api url can be changed so it can run against any server
+pub struct GithubClient {
+    api_url: String,
+    token: String,
+}
+
+impl GithubClient {
+    pub fn new(api_url: &str, token: &str) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header("Authorization", format!("token {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+    }
+
+    /// Gets the release for a tag, or creates it if there is none yet
+    pub fn get_or_create_release(&self, owner: &str, repo: &str, tag: &str) -> GithubRelease {
+        let response = self
+            .authorize(get_agent().get(format!(
+                "{}/repos/{}/{}/releases/tags/{}",
+                self.api_url, owner, repo, tag
+            )))
+            .send()
+            .expect("Request to github failed");
+
+        if response.status() != StatusCode::NOT_FOUND {
+            return response
+                .error_for_status()
+                .expect("Getting the release failed")
+                .json()
+                .expect("Into json failed");
+        }
+
+        println!("Creating release {}", tag.bright_yellow());
+        self.authorize(
+            get_agent().post(format!("{}/repos/{}/{}/releases", self.api_url, owner, repo)),
+        )
+        .json(&serde_json::json!({
+            "tag_name": tag,
+            "name": tag,
+        }))
+        .send()
+        .expect("Request to github failed")
+        .error_for_status()
+        .expect("Creating the release failed")
+        .json()
+        .expect("Into json failed")
+    }
+
+    /// Uploads a file to the release, replacing an asset with the same name, returns the download url
+    pub fn upload_asset(&self, owner: &str, repo: &str, release: &GithubRelease, path: &Path) -> String {
+        let name = path
+            .file_name()
+            .expect("Asset has no file name")
+            .to_string_lossy()
+            .to_string();
+        self.upload_asset_as(owner, repo, release, path, &name)
+    }
+
+    /// Uploads a file to the release under another name, for files that have the same name
+    pub fn upload_asset_as(
+        &self,
+        owner: &str,
+        repo: &str,
+        release: &GithubRelease,
+        path: &Path,
+        name: &str,
+    ) -> String {
+        if let Some(existing) = release.assets.iter().find(|asset| asset.name == name) {
+            println!("Replacing asset {}", name.bright_yellow());
+            self.authorize(get_agent().delete(format!(
+                "{}/repos/{}/{}/releases/assets/{}",
+                self.api_url, owner, repo, existing.id
+            )))
+            .send()
+            .expect("Request to github failed")
+            .error_for_status()
+            .expect("Removing the old asset failed");
+        }
+
+        println!("Uploading {}", path.display().bright_yellow());
+        let upload_url = release
+            .upload_url
+            .split('{')
+            .next()
+            .unwrap_or(&release.upload_url);
+        let asset: GithubAsset = self
+            .authorize(get_agent().post(upload_url))
+            .query(&[("name", name)])
+            .header("Content-Type", "application/octet-stream")
+            .body(std::fs::read(path).expect("Failed to read asset"))
+            .send()
+            .expect("Request to github failed")
+            .error_for_status()
+            .expect("Uploading the asset failed")
+            .json()
+            .expect("Into json failed");
+
+        asset.browser_download_url
+    }
+}
+
+/// Gets the owner and repository name out of a github url
+pub fn parse_repo_url(url: &str) -> Option<(String, String)> {
+    let path = url.split("github.com/").nth(1)?;
+    let mut parts = path.trim_end_matches('/').trim_end_matches(".git").split('/');
+    let owner = parts.next()?.to_string();
+    let repo = parts.next()?.to_string();
+    Some((owner, repo))
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{mock, server_url, Matcher};
+
+    use super::*;
+
+    fn release_json(id: u64, tag: &str, assets: &str) -> String {
+        format!(
+            r#"{{"id": {id}, "tag_name": "{tag}", "upload_url": "{}/uploads/{id}/assets{{?name,label}}", "assets": [{assets}]}}"#,
+            server_url()
+        )
+    }
+
+    #[test]
+    fn creates_missing_release_and_uploads() {
+        let get = mock("GET", "/repos/owner/new-release/releases/tags/v1.0.0")
+            .with_status(404)
+            .create();
+        let create = mock("POST", "/repos/owner/new-release/releases")
+            .match_header("Authorization", "token secret")
+            .with_status(201)
+            .with_body(release_json(1, "v1.0.0", ""))
+            .create();
+        let upload = mock("POST", Matcher::Regex("^/uploads/1/assets".to_string()))
+            .match_query(Matcher::UrlEncoded("name".into(), "libtest.so".into()))
+            .match_body("binary")
+            .with_status(201)
+            .with_body(r#"{"id": 5, "name": "libtest.so", "browser_download_url": "https://example.com/libtest.so"}"#)
+            .create();
+
+        let dir = std::env::temp_dir().join("qpm-github-test-create");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("libtest.so");
+        std::fs::write(&file, "binary").unwrap();
+
+        let client = GithubClient::new(&server_url(), "secret");
+        let release = client.get_or_create_release("owner", "new-release", "v1.0.0");
+        let link = client.upload_asset("owner", "new-release", &release, &file);
+
+        assert_eq!(link, "https://example.com/libtest.so");
+        get.assert();
+        create.assert();
+        upload.assert();
+    }
+
+    #[test]
+    fn replaces_existing_asset() {
+        let get = mock("GET", "/repos/owner/existing/releases/tags/v2.0.0")
+            .with_status(200)
+            .with_body(release_json(
+                2,
+                "v2.0.0",
+                r#"{"id": 7, "name": "mod.qmod", "browser_download_url": "https://example.com/old.qmod"}"#,
+            ))
+            .create();
+        let delete = mock("DELETE", "/repos/owner/existing/releases/assets/7")
+            .with_status(204)
+            .create();
+        let upload = mock("POST", Matcher::Regex("^/uploads/2/assets".to_string()))
+            .with_status(201)
+            .with_body(r#"{"id": 8, "name": "mod.qmod", "browser_download_url": "https://example.com/mod.qmod"}"#)
+            .create();
+
+        let dir = std::env::temp_dir().join("qpm-github-test-replace");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("mod.qmod");
+        std::fs::write(&file, "zip").unwrap();
+
+        let client = GithubClient::new(&server_url(), "secret");
+        let release = client.get_or_create_release("owner", "existing", "v2.0.0");
+        let link = client.upload_asset("owner", "existing", &release, &file);
+
+        assert_eq!(link, "https://example.com/mod.qmod");
+        get.assert();
+        delete.assert();
+        upload.assert();
+    }
+
+    #[test]
+    fn parses_repo_urls() {
+        assert_eq!(
+            parse_repo_url("https://github.com/owner/repo/"),
+            Some(("owner".to_string(), "repo".to_string()))
+        );
+        assert_eq!(
+            parse_repo_url("https://github.com/owner/repo.git"),
+            Some(("owner".to_string(), "repo".to_string()))
+        );
+        assert_eq!(parse_repo_url("https://example.com/file.zip"), None);
+    }
+}
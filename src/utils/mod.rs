@@ -1,3 +1,6 @@
+pub mod elf;
 pub mod git;
+pub mod github;
 pub mod toggle;
-pub mod network;
\ No newline at end of file
+pub mod network;
+pub mod template;
\ No newline at end of file
@@ -0,0 +1,180 @@
+use owo_colors::OwoColorize;
+
+/// A piece of a template
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    /// `${name}` or `${name:-default}`
+    Variable {
+        name: &'a str,
+        default: Option<&'a str>,
+    },
+    /// `${if name}` or `${if !name}`
+    If { name: &'a str, negated: bool },
+    Else,
+    End,
+}
+
+/// Renders a template, using lookup to get the value of variables
+///
+/// - `${name}` is replaced by the value, and an unknown variable is an error
+/// - `${name:-default}` uses default when the variable is unknown
+/// - `${if name}...${else}...${end}` keeps the first part when the variable is set, not empty and not `false`
+/// - `${if !name}...${end}` does the opposite
+/// - `$${` is a literal `${`
+pub fn render(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let tokens = tokenize(template);
+    let mut position = 0;
+    let result = render_block(&tokens, &mut position, &lookup, true);
+
+    match tokens.get(position) {
+        None => result,
+        Some(Token::Else) => panic!("{} without a matching {}", "${else}".bright_red(), "${if}"),
+        Some(_) => panic!("{} without a matching {}", "${end}".bright_red(), "${if}"),
+    }
+}
+
+fn tokenize(template: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("${") {
+        // $${ escapes the variable
+        if start > 0 && rest.as_bytes()[start - 1] == b'$' {
+            tokens.push(Token::Text(&rest[..start]));
+            tokens.push(Token::Text("{"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        tokens.push(Token::Text(&rest[..start]));
+        let end = rest[start..].find('}').unwrap_or_else(|| {
+            panic!(
+                "Unclosed {} in template",
+                rest[start..].lines().next().unwrap_or_default().bright_red()
+            )
+        }) + start;
+
+        let expression = rest[start + 2..end].trim();
+        tokens.push(match expression {
+            "else" => Token::Else,
+            "end" => Token::End,
+            _ => match expression.strip_prefix("if ") {
+                Some(condition) => {
+                    let condition = condition.trim();
+                    match condition.strip_prefix('!') {
+                        Some(name) => Token::If { name: name.trim(), negated: true },
+                        None => Token::If { name: condition, negated: false },
+                    }
+                }
+                None => match expression.split_once(":-") {
+                    Some((name, default)) => Token::Variable { name: name.trim(), default: Some(default) },
+                    None => Token::Variable { name: expression, default: None },
+                },
+            },
+        });
+        rest = &rest[end + 1..];
+    }
+
+    tokens.push(Token::Text(rest));
+    tokens
+}
+
+/// Renders tokens until an else, end or the end of the template, variables are only looked up when active
+fn render_block(
+    tokens: &[Token],
+    position: &mut usize,
+    lookup: &impl Fn(&str) -> Option<String>,
+    active: bool,
+) -> String {
+    let mut result = String::new();
+
+    while let Some(token) = tokens.get(*position) {
+        match token {
+            Token::Else | Token::End => break,
+            Token::Text(text) => {
+                *position += 1;
+                if active {
+                    result.push_str(text);
+                }
+            }
+            Token::Variable { name, default } => {
+                *position += 1;
+                if !active {
+                    continue;
+                }
+
+                let value = lookup(name)
+                    .or_else(|| default.map(str::to_string))
+                    .unwrap_or_else(|| panic!("Unknown template variable {}", name.bright_red()));
+                result.push_str(&value);
+            }
+            Token::If { name, negated } => {
+                *position += 1;
+                let set = active
+                    && lookup(name)
+                        .map(|value| !value.is_empty() && value != "false")
+                        .unwrap_or(false);
+                let condition = set != *negated;
+
+                let then = render_block(tokens, position, lookup, active && condition);
+                let otherwise = if tokens.get(*position) == Some(&Token::Else) {
+                    *position += 1;
+                    render_block(tokens, position, lookup, active && !condition)
+                } else {
+                    String::new()
+                };
+
+                if tokens.get(*position) != Some(&Token::End) {
+                    panic!("{} {} is missing its {}", "${if".bright_red(), name.bright_red(), "${end}");
+                }
+                *position += 1;
+
+                if active {
+                    result.push_str(if condition { &then } else { &otherwise });
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "id" => Some("my-mod".to_string()),
+            "empty" => Some(String::new()),
+            "disabled" => Some("false".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn replaces_variables_and_defaults() {
+        assert_eq!(render("${id} ${missing:-fallback} ${id:-unused}", lookup), "my-mod fallback my-mod");
+        assert_eq!(render("cost: $${id}", lookup), "cost: ${id}");
+    }
+
+    #[test]
+    fn conditionals_pick_a_branch() {
+        let template = "${if id}has ${id}${else}none${end}|${if empty}a${else}b${end}|${if !disabled}c${end}";
+        assert_eq!(render(template, lookup), "has my-mod|b|c");
+        assert_eq!(render("${if missing}${if id}${missing}${end}${end}", lookup), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown template variable")]
+    fn unknown_variables_are_errors() {
+        render("${missing}", lookup);
+    }
+
+    #[test]
+    #[should_panic(expected = "is missing its")]
+    fn unclosed_conditionals_are_errors() {
+        render("${if id}text", lookup);
+    }
+}